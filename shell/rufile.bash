@@ -0,0 +1,12 @@
+# source from ~/.bashrc; `rfcd` leaves the shell in the directory rufile
+# was in when it quit
+rfcd() {
+    local tmp dir
+    tmp="$(mktemp)" || return
+    command rufile --choosedir "$tmp" "$@"
+    dir="$(cat -- "$tmp")"
+    rm -f -- "$tmp"
+    if [ -n "$dir" ] && [ -d "$dir" ] && [ "$dir" != "$PWD" ]; then
+        cd -- "$dir" || return
+    fi
+}
@@ -0,0 +1,11 @@
+# save as ~/.config/fish/functions/rfcd.fish; `rfcd` leaves the shell in
+# the directory rufile was in when it quit
+function rfcd
+    set -l tmp (mktemp); or return
+    command rufile --choosedir $tmp $argv
+    set -l dir (cat -- $tmp)
+    rm -f -- $tmp
+    if test -n "$dir"; and test -d "$dir"; and test "$dir" != "$PWD"
+        cd -- $dir
+    end
+end
@@ -0,0 +1,466 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::os::unix::fs::{PermissionsExt, symlink};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{Local, NaiveDate, TimeZone};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use lzma_rust2::{XzOptions, XzWriter};
+use zip::write::SimpleFileOptions;
+
+use crate::compressed::{strip_extension, Codec};
+use crate::entry::file_data;
+use crate::entry::permissions::FilePermissions;
+use crate::entry::type_parser::FileType;
+
+const S_IFDIR: u32 = 0o040000;
+const S_IFREG: u32 = 0o100000;
+const S_IFLNK: u32 = 0o120000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Tar,
+    TarGz,
+    TarXz,
+    TarZst,
+    Zip,
+}
+
+const EXTENSIONS: [(&str, Format); 8] = [
+    (".tar", Format::Tar),
+    (".tar.gz", Format::TarGz),
+    (".tgz", Format::TarGz),
+    (".tar.xz", Format::TarXz),
+    (".txz", Format::TarXz),
+    (".tar.zst", Format::TarZst),
+    (".tzst", Format::TarZst),
+    (".zip", Format::Zip),
+];
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        split_extension(name).map(|(_, format)| format)
+    }
+
+    // by extension, then by MIME type; a gzip or xz file only counts when
+    // there is a tar inside
+    pub fn detect(path: &Path) -> Option<Format> {
+        let name = path.file_name()?.to_string_lossy();
+        if let Some(format) = Format::from_name(&name) {
+            return Some(format);
+        }
+
+        let format = match file_data::mime_type(path).ok()?.as_str() {
+            "application/x-tar" => return Some(Format::Tar),
+            "application/zip" => return Some(Format::Zip),
+            "application/gzip" | "application/x-gzip" => Format::TarGz,
+            "application/x-xz" => Format::TarXz,
+            "application/zstd" => Format::TarZst,
+            _ => return None,
+        };
+
+        holds_tar(path, format).then_some(format)
+    }
+}
+
+// `a.tar.gz` is `a` and `Format::TarGz`
+pub fn split_extension(name: &str) -> Option<(&str, Format)> {
+    EXTENSIONS
+        .iter()
+        .find_map(|&(extension, format)| Some((strip_extension(name, extension)?, format)))
+}
+
+// one member of an archive; `mode` carries the file type bits like st_mode
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub path: String,
+    pub size: u64,
+    pub mode: u32,
+    pub modified: SystemTime,
+}
+
+impl Entry {
+    pub fn directory(path: String, modified: SystemTime) -> Entry {
+        Entry { path, size: 0, mode: S_IFDIR | 0o755, modified }
+    }
+
+    pub fn is_dir(&self) -> bool {
+        FileType::new(self.mode) == FileType::DIR
+    }
+
+    pub fn mode_string(&self) -> String {
+        format!("{}{}", FileType::new(self.mode), FilePermissions::new(self.mode))
+    }
+
+    // `None` for members that would land outside where they are extracted
+    pub fn relative_path(&self) -> Option<PathBuf> {
+        safe_path(Path::new(&self.path))
+    }
+}
+
+// everything in the archive, in the order it is stored
+pub fn entries(path: &Path, format: Format) -> io::Result<Vec<Entry>> {
+    match format {
+        Format::Zip => zip_entries(path),
+        _ => tar_entries(path, format),
+    }
+}
+
+pub fn total_size(entries: &[Entry]) -> u64 {
+    entries.iter().map(|entry| entry.size).sum()
+}
+
+// writes the members at or below `inner`, or all of them when it is empty,
+// into `dest`; `inner` itself lands at `dest/<its name>`. `progress` gets
+// the number written so far. Returns how many were written
+pub fn extract<F>(path: &Path, format: Format, inner: &Path, dest: &Path,
+                  mut progress: F) -> io::Result<usize>
+        where F: FnMut(usize) {
+    let strip = inner.parent().unwrap_or(Path::new(""));
+    let place = |member: &Path| -> Option<PathBuf> {
+        let member = safe_path(member)?;
+        if !member.starts_with(inner) || member.as_os_str().is_empty() {
+            return None;
+        }
+        target(dest, member.strip_prefix(strip).ok()?)
+    };
+
+    let written = match format {
+        Format::Zip => extract_zip(path, place, &mut progress)?,
+        _ => extract_tar(path, format, place, &mut progress)?,
+    };
+    if written == 0 && !inner.as_os_str().is_empty() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "no such entry in the archive"));
+    }
+
+    Ok(written)
+}
+
+// packs `paths`, each under its own name with everything below it, into
+// a new archive of the kind `target`'s name says. `progress` gets the
+// number of members written and the total
+pub fn create<F>(paths: &[PathBuf], target: &Path, mut progress: F) -> io::Result<usize>
+        where F: FnMut(usize, usize) {
+    let format = target
+        .file_name()
+        .and_then(|name| Format::from_name(&name.to_string_lossy()))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "unknown archive type"))?;
+
+    let mut members = Vec::new();
+    for path in paths {
+        let name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "nothing to name it by"))?;
+        walk(path, PathBuf::from(name), &mut members)?;
+    }
+
+    let file = OpenOptions::new().write(true).create_new(true).open(target)?;
+    let written = match format {
+        Format::Zip => create_zip(file, &members, &mut progress),
+        _ => create_tar(file, format, &members, &mut progress),
+    };
+    // no half written archive is left behind
+    if written.is_err() {
+        fs::remove_file(target).ok();
+    }
+
+    written.map(|_| members.len())
+}
+
+// `path` and what is below it, each with its name in the archive
+fn walk(path: &Path, name: PathBuf, members: &mut Vec<(PathBuf, PathBuf)>) -> io::Result<()> {
+    let is_dir = fs::symlink_metadata(path)?.is_dir();
+    members.push((path.to_path_buf(), name.clone()));
+
+    if is_dir {
+        let mut children = fs::read_dir(path)?.collect::<io::Result<Vec<_>>>()?;
+        children.sort_by_key(|child| child.file_name());
+        for child in children {
+            walk(&child.path(), name.join(child.file_name()), members)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn create_tar(file: File, format: Format, members: &[(PathBuf, PathBuf)],
+              progress: &mut dyn FnMut(usize, usize)) -> io::Result<()> {
+    let writer = BufWriter::new(file);
+    let mut writer = match format {
+        Format::TarGz => {
+            tar_members(GzEncoder::new(writer, Compression::default()), members, progress)?
+                .finish()?
+        }
+        Format::TarXz => {
+            tar_members(XzWriter::new(writer, XzOptions::with_preset(6))?, members, progress)?
+                .finish()?
+        }
+        Format::TarZst => {
+            tar_members(zstd::Encoder::new(writer, 0)?, members, progress)?.finish()?
+        }
+        _ => tar_members(writer, members, progress)?,
+    };
+
+    writer.flush()
+}
+
+// symlinks are stored as links
+fn tar_members<W: Write>(writer: W, members: &[(PathBuf, PathBuf)],
+                         progress: &mut dyn FnMut(usize, usize)) -> io::Result<W> {
+    let mut builder = tar::Builder::new(writer);
+    builder.follow_symlinks(false);
+
+    for (idx, (path, name)) in members.iter().enumerate() {
+        builder.append_path_with_name(path, name)?;
+        progress(idx + 1, members.len());
+    }
+
+    builder.into_inner()
+}
+
+fn create_zip(file: File, members: &[(PathBuf, PathBuf)],
+              progress: &mut dyn FnMut(usize, usize)) -> io::Result<()> {
+    let mut zip = zip::ZipWriter::new(BufWriter::new(file));
+
+    for (idx, (path, name)) in members.iter().enumerate() {
+        let metadata = fs::symlink_metadata(path)?;
+        let options = SimpleFileOptions::default()
+            .unix_permissions(metadata.permissions().mode() & 0o7777)
+            .large_file(metadata.len() >= u32::MAX as u64);
+        let name = name.to_string_lossy();
+
+        if metadata.is_dir() {
+            zip.add_directory(name, options)?;
+        } else if metadata.file_type().is_symlink() {
+            zip.add_symlink(name, fs::read_link(path)?.to_string_lossy(), options)?;
+        } else {
+            zip.start_file(name, options)?;
+            io::copy(&mut File::open(path)?, &mut zip)?;
+        }
+        progress(idx + 1, members.len());
+    }
+
+    zip.finish()?.flush()
+}
+
+// an archive is unpacked next to itself, `a.tar.gz` into `a/`
+pub fn unpack_dir(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy();
+    let stem = match split_extension(&name) {
+        Some((stem, _)) if !stem.is_empty() => stem.to_string(),
+        _ => Path::new(&*name).file_stem()?.to_string_lossy().into_owned(),
+    };
+
+    Some(path.with_file_name(stem)).filter(|dir| dir != path)
+}
+
+// everything in the archive into a new `unpack_dir`, which is returned;
+// `progress` gets the members written and how many there are
+pub fn unpack<F>(path: &Path, format: Format, mut progress: F) -> io::Result<PathBuf>
+        where F: FnMut(usize, usize) {
+    let dest = unpack_dir(path)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "nothing to name it by"))?;
+    let total = entries(path, format)?.len();
+
+    fs::create_dir(&dest)?;
+    extract(path, format, Path::new(""), &dest, |done| progress(done, total))?;
+
+    Ok(dest)
+}
+
+// only plain names, so `../x` and `/etc/x` are refused rather than
+// rewritten
+fn safe_path(path: &Path) -> Option<PathBuf> {
+    let mut safe = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => safe.push(name),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    Some(safe)
+}
+
+// `dest` joined with `member`, unless a symlink on the way could lead
+// somewhere else
+fn target(dest: &Path, member: &Path) -> Option<PathBuf> {
+    let is_symlink = |path: &Path| fs::symlink_metadata(path)
+        .map(|metadata| metadata.file_type().is_symlink())
+        .unwrap_or(false);
+
+    let mut target = dest.to_path_buf();
+    for component in member.components() {
+        target.push(component);
+        if is_symlink(&target) {
+            return None;
+        }
+    }
+
+    Some(target)
+}
+
+fn write_member<R: Read>(reader: &mut R, target: &Path, mode: u32) -> io::Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    match FileType::new(mode) {
+        FileType::DIR => fs::create_dir_all(target),
+        FileType::LNK => {
+            let mut link = String::new();
+            reader.read_to_string(&mut link)?;
+            symlink(link, target)
+        }
+        _ => {
+            io::copy(reader, &mut File::create(target)?)?;
+            fs::set_permissions(target, fs::Permissions::from_mode(mode & 0o777))
+        }
+    }
+}
+
+fn extract_tar<F>(path: &Path, format: Format, place: F,
+                  progress: &mut dyn FnMut(usize)) -> io::Result<usize>
+        where F: Fn(&Path) -> Option<PathBuf> {
+    let mut archive = tar::Archive::new(tar_reader(path, format)?);
+    let mut written = 0;
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let target = match place(&entry.path()?) {
+            Some(target) => target,
+            None => continue,
+        };
+
+        let mode = entry.header().mode()? & 0o7777;
+        match entry.header().entry_type() {
+            tar::EntryType::Directory => write_member(&mut entry, &target, S_IFDIR | mode)?,
+            tar::EntryType::Symlink => {
+                let link = entry.link_name()?.unwrap_or_default().into_owned();
+                let link = link.to_string_lossy().into_owned();
+                write_member(&mut link.as_bytes(), &target, S_IFLNK)?;
+            }
+            tar::EntryType::Regular | tar::EntryType::Continuous => {
+                write_member(&mut entry, &target, S_IFREG | mode)?
+            }
+            // devices, fifos and hard links are left out
+            _ => continue,
+        }
+        written += 1;
+        progress(written);
+    }
+
+    Ok(written)
+}
+
+fn extract_zip<F>(path: &Path, place: F,
+                  progress: &mut dyn FnMut(usize)) -> io::Result<usize>
+        where F: Fn(&Path) -> Option<PathBuf> {
+    let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))?;
+    let mut written = 0;
+
+    for idx in 0..archive.len() {
+        let mut file = archive.by_index(idx)?;
+        let target = match place(Path::new(file.name())) {
+            Some(target) => target,
+            None => continue,
+        };
+
+        let mode = zip_mode(file.unix_mode(), file.is_dir());
+        write_member(&mut file, &target, mode)?;
+        written += 1;
+        progress(written);
+    }
+
+    Ok(written)
+}
+
+// `./a/b/` and `a/b` are the same entry
+fn clean_path(path: &str) -> String {
+    path.trim_start_matches("./").trim_end_matches('/').to_string()
+}
+
+fn tar_reader(path: &Path, format: Format) -> io::Result<Box<dyn Read>> {
+    let file = File::open(path)?;
+
+    match format {
+        Format::TarGz => Codec::Gzip.reader(file),
+        Format::TarXz => Codec::Xz.reader(file),
+        Format::TarZst => Codec::Zstd.reader(file),
+        _ => Ok(Box::new(BufReader::new(file))),
+    }
+}
+
+// a tar header has `ustar` at offset 257
+fn holds_tar(path: &Path, format: Format) -> bool {
+    let mut header = Vec::with_capacity(512);
+    let read = tar_reader(path, format)
+        .and_then(|reader| reader.take(512).read_to_end(&mut header));
+
+    read.is_ok() && header.get(257..262) == Some(b"ustar")
+}
+
+fn tar_entries(path: &Path, format: Format) -> io::Result<Vec<Entry>> {
+    let mut archive = tar::Archive::new(tar_reader(path, format)?);
+    let mut entries = Vec::new();
+
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let header = entry.header();
+        let kind = match header.entry_type() {
+            tar::EntryType::Directory => S_IFDIR,
+            tar::EntryType::Symlink => S_IFLNK,
+            tar::EntryType::Regular | tar::EntryType::Continuous => S_IFREG,
+            // what extracting leaves out isn't listed either
+            _ => continue,
+        };
+
+        entries.push(Entry {
+            path: clean_path(&entry.path()?.to_string_lossy()),
+            size: entry.size(),
+            mode: kind | (header.mode()? & 0o7777),
+            modified: UNIX_EPOCH + Duration::from_secs(header.mtime()?),
+        });
+    }
+
+    Ok(entries)
+}
+
+fn zip_entries(path: &Path) -> io::Result<Vec<Entry>> {
+    let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))?;
+    let mut entries = Vec::with_capacity(archive.len());
+
+    for idx in 0..archive.len() {
+        let file = archive.by_index_raw(idx)?;
+        entries.push(Entry {
+            path: clean_path(file.name()),
+            size: file.size(),
+            mode: zip_mode(file.unix_mode(), file.is_dir()),
+            modified: file.last_modified().and_then(zip_time).unwrap_or(UNIX_EPOCH),
+        });
+    }
+
+    Ok(entries)
+}
+
+// archives made on other systems have no unix mode
+fn zip_mode(mode: Option<u32>, is_dir: bool) -> u32 {
+    match mode {
+        Some(mode) if FileType::new(mode) != FileType::UNDEFINED => mode,
+        Some(mode) if is_dir => S_IFDIR | mode,
+        Some(mode) => S_IFREG | mode,
+        None if is_dir => S_IFDIR | 0o755,
+        None => S_IFREG | 0o644,
+    }
+}
+
+// zip stores local time without a zone
+fn zip_time(time: zip::DateTime) -> Option<SystemTime> {
+    let naive = NaiveDate::from_ymd_opt(time.year() as i32, time.month() as u32, time.day() as u32)?
+        .and_hms_opt(time.hour() as u32, time.minute() as u32, time.second() as u32)?;
+
+    Local.from_local_datetime(&naive).earliest().map(SystemTime::from)
+}
@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use crate::paths;
+
+// single letter quick marks and named bookmarks share one namespace
+#[derive(Debug, Default)]
+pub struct Bookmarks {
+    entries: BTreeMap<String, PathBuf>,
+    file: Option<PathBuf>,
+}
+
+impl Bookmarks {
+    pub fn default_path() -> Option<PathBuf> {
+        let mut path = paths::config_dir()?;
+        path.push("bookmarks.toml");
+
+        Some(path)
+    }
+
+    pub fn load(file: PathBuf) -> Bookmarks {
+        let entries = fs::read_to_string(&file)
+            .ok()
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default();
+
+        Bookmarks { entries, file: Some(file) }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Path> {
+        self.entries.get(name).map(PathBuf::as_path)
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Path)> {
+        self.entries.iter().map(|(name, path)| (name.as_str(), path.as_path()))
+    }
+
+    pub fn set(&mut self, name: &str, path: &Path) -> io::Result<()> {
+        self.entries.insert(name.to_string(), path.to_path_buf());
+        self.save()
+    }
+
+    pub fn remove(&mut self, name: &str) -> io::Result<()> {
+        self.entries.remove(name);
+        self.save()
+    }
+
+    pub fn rename(&mut self, name: &str, new_name: &str) -> io::Result<()> {
+        if new_name.is_empty() || self.entries.contains_key(new_name) {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "bookmark exists"));
+        }
+
+        if let Some(path) = self.entries.remove(name) {
+            self.entries.insert(new_name.to_string(), path);
+        }
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+
+        let text = toml::to_string(&self.entries)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(file, text)
+    }
+}
@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: rufile [OPTIONS]
+
+Options:
+    -c, --config <file>      use <file> instead of $XDG_CONFIG_HOME/rufile/config.toml
+        --choosedir <file>   write the current directory to <file> on exit
+        --choosefile <file>  pick a file and write its path to <file>, `-` for stdout
+        --choosefiles <file> like --choosefile, with the marked files one per line
+    -h, --help               print this help
+
+When choosing files the exit status is 1 if nothing was picked.";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    pub config: Option<PathBuf>,
+    pub choosedir: Option<PathBuf>,
+    pub choosefile: Option<PathBuf>,
+    pub choosefiles: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Args {
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Args, String> {
+        let mut parsed = Args::default();
+
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                parsed.help = true;
+                continue;
+            }
+
+            // `--option file` and `--option=file`
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let slot = match name {
+                "-c" | "--config" => &mut parsed.config,
+                "--choosedir" => &mut parsed.choosedir,
+                "--choosefile" => &mut parsed.choosefile,
+                "--choosefiles" => &mut parsed.choosefiles,
+                _ => return Err(format!("unexpected argument `{}`", arg)),
+            };
+            let file = match value {
+                Some(file) => file,
+                None => args
+                    .next()
+                    .ok_or_else(|| format!("{} requires a file argument", arg))?,
+            };
+            *slot = Some(PathBuf::from(file));
+        }
+
+        if parsed.choosefile.is_some() && parsed.choosefiles.is_some() {
+            return Err(String::from("--choosefile and --choosefiles are exclusive"));
+        }
+
+        Ok(parsed)
+    }
+
+    // where chosen files go, with whether several can be picked
+    pub fn chooser(&self) -> Option<(&PathBuf, bool)> {
+        match (&self.choosefile, &self.choosefiles) {
+            (Some(file), _) => Some((file, false)),
+            (None, Some(file)) => Some((file, true)),
+            (None, None) => None,
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, prelude::*};
+use std::path::PathBuf;
+
+use crate::paths;
+
+const MAX_ENTRIES: usize = 1000;
+
+#[derive(Default)]
+pub struct CommandHistory {
+    entries: Vec<String>,
+    position: Option<usize>,
+    draft: String,
+    file: Option<PathBuf>,
+}
+
+impl CommandHistory {
+    pub fn load(file: PathBuf) -> CommandHistory {
+        let mut entries: Vec<String> = Vec::new();
+
+        if let Ok(content) = fs::read_to_string(&file) {
+            entries = content
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect();
+        }
+
+        let mut history = CommandHistory {
+            entries,
+            file: Some(file),
+            ..CommandHistory::default()
+        };
+
+        if history.entries.len() > MAX_ENTRIES {
+            let excess = history.entries.len() - MAX_ENTRIES;
+            history.entries.drain(..excess);
+            history.rewrite().ok();
+        }
+
+        history
+    }
+
+    pub fn default_path() -> Option<PathBuf> {
+        let mut path = paths::data_dir()?;
+        path.push("history");
+
+        Some(path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&str> {
+        self.entries.get(idx).map(String::as_str)
+    }
+
+    pub fn push(&mut self, line: &str) -> io::Result<()> {
+        self.reset();
+
+        let line = line.trim();
+        if line.is_empty() || line == ":" {
+            return Ok(());
+        }
+        if self.entries.last().map(String::as_str) == Some(line) {
+            return Ok(());
+        }
+
+        self.entries.push(line.to_string());
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+
+        self.append(line)
+    }
+
+    pub fn older(&mut self, current: &str) -> Option<&str> {
+        let idx = match self.position {
+            Some(0) => return None,
+            Some(idx) => idx - 1,
+            None => {
+                if self.is_empty() {
+                    return None;
+                }
+                self.draft = current.to_string();
+                self.entries.len() - 1
+            }
+        };
+
+        self.position = Some(idx);
+        self.get(idx)
+    }
+
+    pub fn newer(&mut self) -> Option<&str> {
+        let idx = self.position?;
+
+        if idx + 1 >= self.entries.len() {
+            self.position = None;
+            return Some(&self.draft);
+        }
+
+        self.position = Some(idx + 1);
+        self.get(idx + 1)
+    }
+
+    pub fn reset(&mut self) {
+        self.position = None;
+        self.draft.clear();
+    }
+
+    // searches backwards starting just before `before`, newest entries first
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        let end = before.min(self.entries.len());
+
+        self.entries[..end]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+
+    fn append(&self, line: &str) -> io::Result<()> {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut out = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file)?;
+        writeln!(out, "{}", line)
+    }
+
+    fn rewrite(&self) -> io::Result<()> {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+
+        let mut content = self.entries.join("\n");
+        content.push('\n');
+        fs::write(file, content)
+    }
+}
@@ -1,13 +1,17 @@
 use std::collections::HashMap;
 use std::io;
+use std::path::Path;
 
+use super::history::CommandHistory;
 use super::operations::OperationExecutor;
+use crate::keymap::Action;
 
 #[derive(Debug, PartialEq)]
 pub enum InputMode {
     Editing,
     Normal,
     Error,
+    Search,
 }
 
 enum OperationError {
@@ -20,7 +24,13 @@ type Operation = fn(&mut OperationExecutor, args: Vec<&str>) -> io::Result<()>;
 
 pub struct CommandHandler {
     pub input: String,
+    pub cursor: usize,
     pub input_mode: InputMode,
+    pub history: CommandHistory,
+    pub search_query: String,
+    search_match: Option<usize>,
+    action: Option<(Action, Option<usize>)>,
+    argument: Option<String>,
     executor: OperationExecutor,
     operations: HashMap<char, Operation>,
 }
@@ -38,7 +48,13 @@ impl Default for CommandHandler {
         
         CommandHandler {
             input: String::new(),
+            cursor: 0,
             input_mode: InputMode::Normal,
+            history: CommandHistory::default(),
+            search_query: String::new(),
+            search_match: None,
+            action: None,
+            argument: None,
             executor: OperationExecutor::default(),
             operations: ops,
         }
@@ -47,11 +63,83 @@ impl Default for CommandHandler {
 
 impl CommandHandler {
     pub fn exec(&mut self, file_name: Option<&str>) {
+        self.history.push(&self.input).ok();
+        self.execute_input(file_name);
+    }
+
+    pub fn run(&mut self, line: &str, file_name: Option<&str>) {
+        self.input = line.to_string();
+        self.execute_input(file_name);
+    }
+
+    pub fn transfer(&mut self, file_name: Option<&str>, dir: &Path, remove: bool) {
+        self.input_mode = match file_name.map(|name| self.executor.transfer(name, dir, remove)) {
+            Some(Ok(())) => InputMode::Normal,
+            _ => InputMode::Error,
+        };
+    }
+
+    pub fn take_action(&mut self) -> Option<(Action, Option<usize>)> {
+        self.action.take()
+    }
+
+    pub fn take_argument(&mut self) -> Option<String> {
+        self.argument.take()
+    }
+
+    fn execute_input(&mut self, file_name: Option<&str>) {
+        self.cursor = 0;
+
+        // `:!cmd` and `:&cmd` are short for `:shell cmd` and `:shell_background cmd`
+        let line = self.input.trim_start();
+        let shell = match (line.strip_prefix(":!"), line.strip_prefix(":&")) {
+            (Some(rest), _) => Some((Action::Shell, rest)),
+            (_, Some(rest)) => Some((Action::ShellBackground, rest)),
+            _ => None,
+        };
+        if let Some((action, rest)) = shell {
+            self.argument = Some(rest.trim().to_string());
+            self.action = Some((action, None));
+            self.input_mode = InputMode::Normal;
+            self.input.drain(..);
+            return;
+        }
+
         let command :Vec<&str> = self.input
             .split_ascii_whitespace()
             .collect();
 
-        if command[0].len() != 2 {
+        if let Some(action) = command.first()
+                .and_then(|name| name.strip_prefix(':'))
+                .and_then(|name| name.parse::<Action>().ok()) {
+            if action.takes_argument() {
+                let argument = self.input.trim_start()[command[0].len()..].trim();
+                self.argument = Some(argument.to_string());
+                self.action = Some((action, None));
+                self.input_mode = InputMode::Normal;
+                self.input.drain(..);
+                return;
+            }
+
+            self.input_mode = match command.len() {
+                1 => {
+                    self.action = Some((action, None));
+                    InputMode::Normal
+                }
+                2 => match command[1].parse::<usize>() {
+                    Ok(count) => {
+                        self.action = Some((action, Some(count)));
+                        InputMode::Normal
+                    }
+                    Err(_) => InputMode::Error,
+                },
+                _ => InputMode::Error,
+            };
+            self.input.drain(..);
+            return;
+        }
+
+        if command.is_empty() || command[0].len() != 2 {
             self.input_mode = InputMode::Error;
             self.input.drain(..);
             return;
@@ -92,6 +180,151 @@ impl CommandHandler {
         self.input.drain(..);
     }
 
+    pub fn start_editing(&mut self) {
+        self.start_command(":");
+    }
+
+    pub fn start_command(&mut self, line: &str) {
+        self.input = line.to_string();
+        self.cursor = self.input.len();
+        self.input_mode = InputMode::Editing;
+    }
+
+    pub fn cancel(&mut self) {
+        self.input.drain(..);
+        self.cursor = 0;
+        self.history.reset();
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn cursor_column(&self) -> u16 {
+        self.input[..self.cursor].chars().count() as u16
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.input.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    pub fn backspace(&mut self) {
+        if self.input.is_empty() {
+            self.cancel();
+            return;
+        }
+
+        if let Some(prev) = self.prev_boundary(self.cursor) {
+            self.input.remove(prev);
+            self.cursor = prev;
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.cursor < self.input.len() {
+            self.input.remove(self.cursor);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        if let Some(prev) = self.prev_boundary(self.cursor) {
+            self.cursor = prev;
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if let Some(c) = self.input[self.cursor..].chars().next() {
+            self.cursor += c.len_utf8();
+        }
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.input.len();
+    }
+
+    pub fn delete_word(&mut self) {
+        let before = &self.input[..self.cursor];
+        let trimmed = before.trim_end();
+        let start = trimmed
+            .rfind(char::is_whitespace)
+            .map(|idx| idx + 1)
+            .unwrap_or(0);
+
+        self.input.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    pub fn kill_to_start(&mut self) {
+        self.input.drain(..self.cursor);
+        self.cursor = 0;
+    }
+
+    pub fn kill_to_end(&mut self) {
+        self.input.truncate(self.cursor);
+    }
+
+    pub fn history_prev(&mut self) {
+        if let Some(line) = self.history.older(&self.input) {
+            self.input = line.to_string();
+            self.cursor = self.input.len();
+        }
+    }
+
+    pub fn history_next(&mut self) {
+        if let Some(line) = self.history.newer() {
+            self.input = line.to_string();
+            self.cursor = self.input.len();
+        }
+    }
+
+    pub fn start_search(&mut self) {
+        self.search_query.drain(..);
+        self.search_match = None;
+        self.input_mode = InputMode::Search;
+    }
+
+    pub fn search_matched(&self) -> Option<&str> {
+        self.search_match.and_then(|idx| self.history.get(idx))
+    }
+
+    pub fn search_push(&mut self, c: char) {
+        self.search_query.push(c);
+        self.search_match = self.history.search(&self.search_query, self.history.len());
+    }
+
+    pub fn search_pop(&mut self) {
+        self.search_query.pop();
+        self.search_match = self.history.search(&self.search_query, self.history.len());
+    }
+
+    pub fn search_older(&mut self) {
+        let before = self.search_match.unwrap_or(self.history.len());
+        if let Some(idx) = self.history.search(&self.search_query, before) {
+            self.search_match = Some(idx);
+        }
+    }
+
+    pub fn accept_search(&mut self) {
+        if let Some(idx) = self.search_match {
+            if let Some(line) = self.history.get(idx) {
+                self.input = line.to_string();
+                self.cursor = self.input.len();
+            }
+        }
+        self.input_mode = InputMode::Editing;
+    }
+
+    pub fn cancel_search(&mut self) {
+        self.search_match = None;
+        self.input_mode = InputMode::Editing;
+    }
+
+    fn prev_boundary(&self, idx: usize) -> Option<usize> {
+        self.input[..idx].char_indices().last().map(|(i, _)| i)
+    }
+
     fn validate_permissions(&self, perms: &str) -> Result<(), OperationError> {
         if perms.len() != 3 {
             return Err(OperationError::InvalidArgument);
@@ -1,8 +1,10 @@
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
-use std::{env, fs, io};
+use std::{fs, io};
 use std::os::unix::fs::PermissionsExt;
 
+use crate::vfs;
+
 pub struct OperationExecutor {
     last_operation: char,
     last_op_file_path: PathBuf,
@@ -19,12 +21,21 @@ impl Default for OperationExecutor {
 
 impl OperationExecutor {
     fn save_file_path(&mut self, file_name: &str) -> io::Result<()> {
-        self.last_op_file_path.push(env::current_dir()?);
+        self.last_op_file_path.push(vfs::current_dir()?);
         self.last_op_file_path.push(file_name);
 
         Ok(())
     }
 
+    // nothing is written inside an archive
+    fn writable(&self) -> io::Result<()> {
+        if vfs::in_archive(&vfs::current_dir()?) {
+            return Err(vfs::read_only());
+        }
+
+        Ok(())
+    }
+
     fn is_file(&self) -> bool {
         self.last_op_file_path.is_file()
     }
@@ -45,7 +56,7 @@ impl OperationExecutor {
     }
 
     fn copy_dir(&self, _dir_name: &OsStr) -> io::Result<()> {
-        let mut dst = env::current_dir()?;
+        let mut dst = vfs::current_dir()?;
         dst.push(self.last_op_file_path.file_name().unwrap());
 
         self.copy_recursively(&self.last_op_file_path, &dst)?;
@@ -71,6 +82,7 @@ impl OperationExecutor {
 
     pub fn cut(&mut self, args: Vec<&str>) -> io::Result<()> {
         let file_name = args[0];
+        self.writable()?;
 
         self.save_file_path(file_name)?;
         self.last_operation = 'm';
@@ -79,9 +91,13 @@ impl OperationExecutor {
     }
 
     pub fn paste(&mut self, _args: Vec<&str>) -> io::Result<()> {
+        self.writable()?;
         let file = self.last_op_file_path.file_name().unwrap();
 
-        if self.last_operation == 'c' {
+        // copying out of an archive extracts
+        if self.last_operation == 'c' && vfs::is_member(&self.last_op_file_path) {
+            vfs::copy_out(&self.last_op_file_path, &vfs::current_dir()?)?;
+        } else if self.last_operation == 'c' {
             if self.is_file() {
                 self.copy_file(&file)?;
             } else { // copy directory
@@ -102,8 +118,45 @@ impl OperationExecutor {
         Ok(())
     }
 
+    // copies or moves `file_name` from the working directory into `dir`
+    pub fn transfer(&self, file_name: &str, dir: &Path, remove: bool) -> io::Result<()> {
+        let src = vfs::current_dir()?.join(file_name);
+        let dst = dir.join(file_name);
+
+        if vfs::in_archive(dir) || (remove && vfs::is_member(&src)) {
+            return Err(vfs::read_only());
+        }
+        if dst.exists() {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "destination exists"));
+        }
+        if vfs::is_member(&src) {
+            return vfs::copy_out(&src, dir);
+        }
+        if remove && fs::rename(&src, &dst).is_ok() {
+            return Ok(());
+        }
+
+        if src.is_dir() {
+            if dst.starts_with(&src) {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "destination inside source"));
+            }
+            self.copy_recursively(&src, &dst)?;
+            if remove {
+                fs::remove_dir_all(&src)?;
+            }
+        } else {
+            fs::copy(&src, &dst)?;
+            if remove {
+                fs::remove_file(&src)?;
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn delete(&mut self, args: Vec<&str>) -> io::Result<()> {
         let file_name = args[0];
+        self.writable()?;
 
         self.save_file_path(&file_name)?;
 
@@ -119,6 +172,7 @@ impl OperationExecutor {
     pub fn rename(&mut self, args: Vec<&str>) -> io::Result<()> {
         let file_name = args[0];
         let new_name = args[1];
+        self.writable()?;
 
         fs::rename(file_name, new_name)?;
 
@@ -127,6 +181,7 @@ impl OperationExecutor {
 
     pub fn create(&mut self, args: Vec<&str>) -> io::Result<()> {
         let file_name = args[1];
+        self.writable()?;
 
         if args[0] == "d" { // directory
             fs::create_dir(file_name)?;
@@ -140,6 +195,7 @@ impl OperationExecutor {
     pub fn edit(&mut self, args: Vec<&str>) -> io::Result<()> {
         let file_name = args[0];
         let modes = u32::from_str_radix(args[1], 8).unwrap();
+        self.writable()?;
         fs::set_permissions(file_name, fs::Permissions::from_mode(modes))?;
 
         Ok(())
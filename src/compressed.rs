@@ -0,0 +1,106 @@
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+use lzma_rust2::XzReader;
+
+use crate::entry::file_data;
+
+// a single compressed stream, not an archive of several files
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Codec {
+    Gzip,
+    Xz,
+    Zstd,
+    Bzip2,
+}
+
+const EXTENSIONS: [(&str, Codec); 4] = [
+    (".gz", Codec::Gzip),
+    (".xz", Codec::Xz),
+    (".zst", Codec::Zstd),
+    (".bz2", Codec::Bzip2),
+];
+
+impl Codec {
+    // `app.log.gz` is `app.log` and gzip
+    pub fn split_extension(name: &str) -> Option<(&str, Codec)> {
+        EXTENSIONS
+            .iter()
+            .find_map(|&(extension, codec)| Some((strip_extension(name, extension)?, codec)))
+    }
+
+    // by extension, then by MIME type; the name is what the file is called
+    // once decompressed
+    pub fn detect(path: &Path) -> Option<(String, Codec)> {
+        let name = path.file_name()?.to_string_lossy();
+        if let Some((stem, codec)) = Codec::split_extension(&name) {
+            return Some((stem.to_string(), codec));
+        }
+
+        let codec = match file_data::mime_type(path).ok()?.as_str() {
+            "application/gzip" | "application/x-gzip" => Codec::Gzip,
+            "application/x-xz" => Codec::Xz,
+            "application/zstd" => Codec::Zstd,
+            "application/x-bzip2" => Codec::Bzip2,
+            _ => return None,
+        };
+
+        Some((name.into_owned(), codec))
+    }
+
+    pub fn reader<R: Read + 'static>(self, inner: R) -> io::Result<Box<dyn Read>> {
+        let inner = BufReader::new(inner);
+
+        Ok(match self {
+            Codec::Gzip => Box::new(MultiGzDecoder::new(inner)),
+            Codec::Xz => Box::new(XzReader::new(inner, true)),
+            Codec::Zstd => Box::new(zstd::Decoder::with_buffer(inner)?),
+            Codec::Bzip2 => Box::new(MultiBzDecoder::new(inner)),
+        })
+    }
+}
+
+impl Display for Codec {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Codec::Gzip => "gzip",
+            Codec::Xz => "xz",
+            Codec::Zstd => "zstd",
+            Codec::Bzip2 => "bzip2",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+// `name` without `extension`, whatever its case
+pub fn strip_extension<'a>(name: &'a str, extension: &str) -> Option<&'a str> {
+    let stem = name.len().checked_sub(extension.len())?;
+    let matches = name.is_char_boundary(stem) && name[stem..].eq_ignore_ascii_case(extension);
+
+    matches.then(|| &name[..stem])
+}
+
+// up to `limit` bytes from the start of the decompressed stream; a stream
+// that breaks off still gives what came before
+pub fn head(path: &Path, codec: Codec, limit: usize) -> io::Result<Vec<u8>> {
+    let mut reader = codec.reader(File::open(path)?)?.take(limit as u64);
+    let mut head = Vec::with_capacity(limit.min(64 * 1024));
+    let mut buf = [0; 8192];
+
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(read) => head.extend_from_slice(&buf[..read]),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(_) if !head.is_empty() => break,
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(head)
+}
@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::entry::listing::{ListingOptions, SortKey};
+use crate::keymap::{self, Action, KeyMap};
+use crate::paths;
+use crate::theme::{self, Theme, THEME_NAMES};
+use crate::theme::ls_colors::LsColors;
+use crate::view::ViewMode;
+use crate::view::columns::{Column, DEFAULT_COLUMNS};
+
+#[derive(Debug)]
+pub struct ConfigError {
+    pub path: Option<PathBuf>,
+    pub message: String,
+}
+
+impl ConfigError {
+    fn new(message: String) -> ConfigError {
+        ConfigError { path: None, message }
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}: {}", path.display(), self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl error::Error for ConfigError {}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    general: GeneralSection,
+    layout: LayoutSection,
+    theme: HashMap<String, String>,
+    keys: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct GeneralSection {
+    tick_rate: u64,
+    show_hidden: bool,
+    sort: String,
+    sort_reverse: bool,
+    dirs_first: bool,
+    ls_colors: bool,
+    restore_tabs: bool,
+}
+
+impl Default for GeneralSection {
+    fn default() -> GeneralSection {
+        GeneralSection {
+            tick_rate: 200,
+            show_hidden: true,
+            sort: String::from("name"),
+            sort_reverse: false,
+            dirs_first: true,
+            ls_colors: true,
+            restore_tabs: false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LayoutSection {
+    list_width: u16,
+    preview_height: u16,
+    info_height: u16,
+    long_listing: bool,
+    columns: Vec<String>,
+    view: String,
+    miller_ratios: Vec<u16>,
+}
+
+impl Default for LayoutSection {
+    fn default() -> LayoutSection {
+        LayoutSection {
+            list_width: 50,
+            preview_height: 12,
+            info_height: 6,
+            long_listing: false,
+            columns: DEFAULT_COLUMNS
+                .iter()
+                .map(|column| column.title().to_lowercase())
+                .collect(),
+            view: String::from("split"),
+            miller_ratios: vec![1, 3, 4],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutConfig {
+    pub list_width: u16,
+    pub preview_height: u16,
+    pub info_height: u16,
+    pub long_listing: bool,
+    pub columns: Vec<Column>,
+    pub view: ViewMode,
+    pub miller_ratios: Vec<u16>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub tick_rate: Duration,
+    pub listing: ListingOptions,
+    pub layout: LayoutConfig,
+    pub theme: Theme,
+    pub use_ls_colors: bool,
+    pub ls_colors: LsColors,
+    pub restore_tabs: bool,
+    pub keymap: KeyMap,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config::from_file(ConfigFile::default()).unwrap()
+    }
+}
+
+impl Config {
+    pub fn default_path() -> Option<PathBuf> {
+        let mut path = paths::config_dir()?;
+        path.push("config.toml");
+
+        Some(path)
+    }
+
+    // an explicitly requested file has to exist, the default one is optional
+    pub fn load(path: Option<&Path>) -> Result<Config, ConfigError> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match Config::default_path() {
+                Some(path) => (path, false),
+                None => {
+                    let mut config = Config::default();
+                    config.load_ls_colors();
+                    return Ok(config);
+                }
+            },
+        };
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound && !required => {
+                let mut config = Config::default();
+                config.load_ls_colors();
+                return Ok(config);
+            }
+            Err(err) => {
+                return Err(ConfigError {
+                    path: Some(path),
+                    message: err.to_string(),
+                });
+            }
+        };
+
+        let mut config = Config::parse(&content).map_err(|err| ConfigError {
+            path: Some(path),
+            ..err
+        })?;
+        config.load_ls_colors();
+
+        Ok(config)
+    }
+
+    // $LS_COLORS wins over a dircolors database next to config.toml
+    pub fn load_ls_colors(&mut self) {
+        if !self.use_ls_colors {
+            return;
+        }
+
+        if let Some(ls_colors) = LsColors::from_env() {
+            self.ls_colors = ls_colors;
+        } else if let Some(mut path) = paths::config_dir() {
+            path.push("dircolors");
+            if let Ok(database) = fs::read_to_string(path) {
+                self.ls_colors = LsColors::parse_dircolors(&database);
+            }
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Config, ConfigError> {
+        let file: ConfigFile = toml::from_str(content)
+            .map_err(|err| ConfigError::new(err.to_string()))?;
+
+        Config::from_file(file)
+    }
+
+    fn from_file(file: ConfigFile) -> Result<Config, ConfigError> {
+        let general = file.general;
+        if general.tick_rate == 0 {
+            return Err(ConfigError::new(
+                "general.tick_rate: must be greater than 0".to_string()));
+        }
+
+        let sort: SortKey = general.sort
+            .parse()
+            .map_err(|err| ConfigError::new(format!("general.sort: {}", err)))?;
+
+        let layout = file.layout;
+        if !(10..=90).contains(&layout.list_width) {
+            return Err(ConfigError::new(format!(
+                "layout.list_width: must be between 10 and 90, got {}",
+                layout.list_width)));
+        }
+        // rows, borders included; more than a terminal has squeezes out the rest
+        let heights = [("preview_height", layout.preview_height), ("info_height", layout.info_height)];
+        for (name, height) in heights {
+            if !(3..=50).contains(&height) {
+                return Err(ConfigError::new(format!(
+                    "layout.{}: must be between 3 and 50, got {}", name, height)));
+            }
+        }
+
+        if layout.columns.is_empty() {
+            return Err(ConfigError::new(
+                "layout.columns: at least one column is required".to_string()));
+        }
+        let columns = layout.columns
+            .iter()
+            .map(|name| name.parse::<Column>())
+            .collect::<Result<Vec<Column>, String>>()
+            .map_err(|err| ConfigError::new(format!("layout.columns: {}", err)))?;
+
+        let view: ViewMode = layout.view
+            .parse()
+            .map_err(|err| ConfigError::new(format!("layout.view: {}", err)))?;
+
+        if layout.miller_ratios.len() != 3 || layout.miller_ratios.contains(&0) {
+            return Err(ConfigError::new(format!(
+                "layout.miller_ratios: expected three ratios greater than 0, got {:?}",
+                layout.miller_ratios)));
+        }
+
+        Ok(Config {
+            tick_rate: Duration::from_millis(general.tick_rate),
+            listing: ListingOptions {
+                show_hidden: general.show_hidden,
+                sort,
+                reverse: general.sort_reverse,
+                dirs_first: general.dirs_first,
+                filter: None,
+            },
+            layout: LayoutConfig {
+                list_width: layout.list_width,
+                preview_height: layout.preview_height,
+                info_height: layout.info_height,
+                long_listing: layout.long_listing,
+                columns,
+                view,
+                miller_ratios: layout.miller_ratios,
+            },
+            theme: parse_theme(&file.theme)?,
+            use_ls_colors: general.ls_colors,
+            ls_colors: LsColors::default(),
+            restore_tabs: general.restore_tabs,
+            keymap: parse_keys(&file.keys)?,
+        })
+    }
+}
+
+fn parse_theme(colors: &HashMap<String, String>) -> Result<Theme, ConfigError> {
+    let mut theme = Theme::default();
+
+    if let Some(name) = colors.get("name") {
+        theme = Theme::named(name).ok_or_else(|| ConfigError::new(format!(
+            "theme.name: unknown theme `{}`, expected one of {}",
+            name, THEME_NAMES.join(", "))))?;
+    }
+
+    for (name, value) in colors.iter().filter(|(name, _)| *name != "name") {
+        let color = theme::parse_color(value)
+            .map_err(|err| ConfigError::new(format!("theme.{}: {}", name, err)))?;
+
+        let slot = match name.as_str() {
+            "text" => &mut theme.text,
+            "list_border" => &mut theme.list_border,
+            "preview_border" => &mut theme.preview_border,
+            "info_border" => &mut theme.info_border,
+            "directory" => &mut theme.directory,
+            "link" => &mut theme.link,
+            "executable" => &mut theme.executable,
+            "socket" => &mut theme.socket,
+            "fifo" => &mut theme.fifo,
+            "device" => &mut theme.device,
+            "orphan" => &mut theme.orphan,
+            "highlight_fg" => &mut theme.highlight_fg,
+            "highlight_bg" => &mut theme.highlight_bg,
+            "error" => &mut theme.error,
+            _ => {
+                return Err(ConfigError::new(format!(
+                    "theme: unknown color slot `{}`", name)));
+            }
+        };
+        *slot = color;
+    }
+
+    Ok(theme)
+}
+
+fn parse_keys(keys: &HashMap<String, String>) -> Result<KeyMap, ConfigError> {
+    let mut keymap = KeyMap::default();
+
+    for (keys, action) in keys {
+        let parsed_keys = keymap::parse_keys(keys)
+            .map_err(|err| ConfigError::new(format!("keys: {}", err)))?;
+
+        if action == "none" {
+            keymap.unbind(&parsed_keys);
+            continue;
+        }
+
+        let parsed_action: Action = action
+            .parse()
+            .map_err(|err| ConfigError::new(format!("keys.{}: {}", keys, err)))?;
+        keymap.bind(parsed_keys, parsed_action);
+    }
+
+    Ok(keymap)
+}
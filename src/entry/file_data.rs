@@ -1,22 +1,33 @@
-use std::io::{BufReader, Error, prelude::*};
-use std::fs::{DirEntry, File};
+use std::io::{self, BufReader, Error, prelude::*};
+use std::fs::{self, DirEntry, File};
+use std::path::{Path, PathBuf};
 use std::os::linux::fs::MetadataExt;
 use std::time::SystemTime;
 use std::fmt::{self, Display, Formatter};
 use chrono::{DateTime, Local};
-use filemagic::{FileMagicError, magic};
+use filemagic::Magic;
+use filemagic::flags::Flags;
 use humansize::{FileSize, file_size_opts};
 
+use crate::archive::Entry;
 use super::permissions::FilePermissions;
 use super::type_parser::FileType;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct FileData {
     pub name: String,
+    path: PathBuf,
     file_type: FileType,
     permissions: FilePermissions,
+    // st_mode with the setuid, setgid and sticky bits
+    mode: u32,
     mod_time: SystemTime,
     file_size: u64,
+    target_type: Option<FileType>,
+    uid: u32,
+    gid: u32,
+    inode: u64,
+    links: u64,
 }
 
 impl FileData {
@@ -24,57 +35,134 @@ impl FileData {
         let metadata = entry.metadata()?;
         let file_type = FileType::new(metadata.st_mode());
         let permissions = FilePermissions::new(metadata.st_mode());
+
+        let mut target_type = None;
+        if file_type == FileType::LNK {
+            if let Ok(target) = fs::metadata(entry.path()) {
+                target_type = Some(FileType::new(target.st_mode()));
+            }
+        }
         
         Ok(FileData {
             name: entry.file_name().into_string().unwrap(),
+            path: entry.path(),
             file_type,
             permissions,
+            mode: metadata.st_mode(),
             mod_time: metadata.modified()?,
             file_size: metadata.len(),
+            target_type,
+            uid: metadata.st_uid(),
+            gid: metadata.st_gid(),
+            inode: metadata.st_ino(),
+            links: metadata.st_nlink(),
         })
     }
 
-    pub fn preview(&self) -> Result<String, Error> {
-        let file = File::open(&self.name)?;
-        let lines = BufReader::new(&file)
-            .lines()
-            .take(10);
-            
-        let mut head: Vec<String> = Vec::with_capacity(10);
-        
-        for line in lines {
-            if let Ok(line) = line {
-                head.push(line);
-            } else {
-                return Ok("".to_string());
+    // an archive member, listed as if it were in the directory `path` is in
+    pub fn from_entry(path: PathBuf, entry: &Entry) -> FileData {
+        FileData {
+            name: path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+            path,
+            file_type: FileType::new(entry.mode),
+            permissions: FilePermissions::new(entry.mode),
+            mode: entry.mode,
+            mod_time: entry.modified,
+            file_size: entry.size,
+            target_type: None,
+            uid: 0,
+            gid: 0,
+            inode: 0,
+            links: 1,
+        }
+    }
+
+    // the first `lines` lines, none at all if the file isn't UTF-8
+    pub fn preview(&self, lines: usize) -> Result<Vec<String>, Error> {
+        let file = File::open(&self.path)?;
+        let mut head: Vec<String> = Vec::with_capacity(lines);
+
+        for line in BufReader::new(&file).lines().take(lines) {
+            match line {
+                Ok(line) => head.push(line),
+                Err(_) => return Ok(Vec::new()),
             }
         }
-            
-        Ok(head.join("\n"))
+
+        Ok(head)
     }
 
-    pub fn get_mime_type(&self) -> Result<String, FileMagicError> {
-        let file = &self.name;
-        let magic = magic!().expect("error");
-  
-        magic.file(file)
+    pub fn get_mime_type(&self) -> io::Result<String> {
+        mime_type(&self.path)
     }
 
     pub fn info(&self) -> String {
-        let mut mime_type = String::new();
+        self.info_with_mime(&self.get_mime_type().unwrap_or_default())
+    }
 
-        if let Ok(mtype) = self.get_mime_type() {
-            mime_type = mtype;
-        }
+    // with a MIME type that was already looked up
+    pub fn info_with_mime(&self, mime_type: &str) -> String {
         let mod_time: DateTime<Local> = self.mod_time.into();
 
-        format!("{}{}\n{}\n{}\n{}", self.file_type, 
-            self.permissions,
-            self.file_size.file_size(file_size_opts::DECIMAL).unwrap(),
+        format!("{}\n{}\n{}\n{}", self.mode_string(),
+            self.size_string(),
             mod_time.format("%b %e %T"),
             mime_type)
     }
 
+    pub fn mode_string(&self) -> String {
+        format!("{}{}", self.file_type, self.permissions)
+    }
+
+    pub fn size_string(&self) -> String {
+        self.file_size.file_size(file_size_opts::DECIMAL).unwrap()
+    }
+
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    pub fn gid(&self) -> u32 {
+        self.gid
+    }
+
+    pub fn inode(&self) -> u64 {
+        self.inode
+    }
+
+    pub fn links(&self) -> u64 {
+        self.links
+    }
+
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    // the type of the file a symlink points to, `None` for broken links
+    pub fn target_type(&self) -> Option<FileType> {
+        self.target_type
+    }
+
+    pub fn mode(&self) -> u32 {
+        self.mode
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.permissions.is_executable()
+    }
+
+    pub fn size(&self) -> u64 {
+        self.file_size
+    }
+
+    pub fn modified(&self) -> SystemTime {
+        self.mod_time
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     pub fn is_dir(&self) -> bool {
         self.file_type == FileType::DIR
     }
@@ -84,6 +172,32 @@ impl FileData {
     }
 }
 
+thread_local! {
+    // loading the database is the slow part, so each thread does it once;
+    // libmagic handles can't be shared between threads
+    static MAGIC: Option<Magic> = open_magic();
+}
+
+// without MIME_TYPE libmagic describes the file ("PNG image data, ...")
+fn open_magic() -> Option<Magic> {
+    let magic = Magic::open(Flags::MIME_TYPE | Flags::SYMLINK).ok()?;
+    magic.load::<String>(&[]).ok()?;
+    Some(magic)
+}
+
+// `image/png`, following symlinks
+pub fn mime_type(path: &Path) -> io::Result<String> {
+    MAGIC.with(|magic| {
+        let magic = magic
+            .as_ref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no magic database"))?;
+
+        magic
+            .file(path.to_string_lossy().as_ref())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "unknown file type"))
+    })
+}
+
 impl Display for FileData {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "{}\n{}", self.name,self.info())    
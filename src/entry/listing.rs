@@ -0,0 +1,122 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::file_data::FileData;
+use crate::vfs;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortKey {
+    None,
+    Name,
+    Size,
+    Modified,
+    Extension,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<SortKey, String> {
+        match name {
+            "none" => Ok(SortKey::None),
+            "name" => Ok(SortKey::Name),
+            "size" => Ok(SortKey::Size),
+            "modified" | "mtime" => Ok(SortKey::Modified),
+            "extension" | "ext" => Ok(SortKey::Extension),
+            _ => Err(format!("unknown sort key `{}`, expected one of \
+                              none, name, size, modified, extension", name)),
+        }
+    }
+}
+
+impl Display for SortKey {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            SortKey::None => "none",
+            SortKey::Name => "name",
+            SortKey::Size => "size",
+            SortKey::Modified => "modified",
+            SortKey::Extension => "extension",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ListingOptions {
+    pub show_hidden: bool,
+    pub sort: SortKey,
+    pub reverse: bool,
+    pub dirs_first: bool,
+    pub filter: Option<String>,
+}
+
+impl Default for ListingOptions {
+    fn default() -> ListingOptions {
+        ListingOptions {
+            show_hidden: true,
+            sort: SortKey::Name,
+            reverse: false,
+            dirs_first: true,
+            filter: None,
+        }
+    }
+}
+
+impl ListingOptions {
+    pub fn is_visible(&self, name: &str) -> bool {
+        let matches_filter = match &self.filter {
+            Some(filter) => name.to_lowercase().contains(&filter.to_lowercase()),
+            None => true,
+        };
+
+        matches_filter && (self.show_hidden || !name.starts_with('.'))
+    }
+
+    pub fn apply(&self, files: &mut Vec<FileData>) {
+        files.retain(|file| self.is_visible(&file.name));
+
+        if self.sort != SortKey::None {
+            files.sort_by(|a, b| {
+                let order = self.compare(a, b);
+                if self.reverse { order.reverse() } else { order }
+            });
+        }
+
+        if self.dirs_first {
+            // stable, so the order within each group is kept
+            files.sort_by_key(|file| !file.is_dir());
+        }
+    }
+
+    fn compare(&self, a: &FileData, b: &FileData) -> Ordering {
+        let by_name = || a.name.to_lowercase().cmp(&b.name.to_lowercase());
+
+        match self.sort {
+            SortKey::None => Ordering::Equal,
+            SortKey::Name => by_name(),
+            SortKey::Size => a.size().cmp(&b.size()).then_with(by_name),
+            SortKey::Modified => a.modified().cmp(&b.modified()).then_with(by_name),
+            SortKey::Extension => {
+                let ext = |name: &str| Path::new(name)
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                ext(&a.name).cmp(&ext(&b.name)).then_with(by_name)
+            }
+        }
+    }
+}
+
+// on disk or inside an archive
+pub fn read_dir(path: &Path, opts: &ListingOptions) -> io::Result<Vec<FileData>> {
+    let mut files = vfs::read_dir(path)?;
+
+    opts.apply(&mut files);
+
+    Ok(files)
+}
@@ -1,4 +1,4 @@
 pub mod file_data;
-
+pub mod listing;
 pub mod permissions;
 pub mod type_parser;
@@ -1,6 +1,6 @@
 use std::fmt::{self, Display, Formatter};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct FilePermissions {
     perms: [char; 9] 
 }
@@ -19,6 +19,10 @@ impl FilePermissions {
 
         FilePermissions{ perms }
     }
+
+    pub fn is_executable(&self) -> bool {
+        self.perms[2] == 'x' || self.perms[5] == 'x' || self.perms[8] == 'x'
+    }
 }
 
 impl Display for FilePermissions {
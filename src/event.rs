@@ -1,5 +1,10 @@
-use std::{io, sync::mpsc, thread, time::Duration};
-use termion::{event::Key, input::TermRead};
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::{thread, time::Duration};
+use termion::event::{self as term_event, Key};
+
+use crate::keymap;
 
 pub struct EventConfig {
     tick_rate: Duration,
@@ -18,31 +23,68 @@ pub enum Event<I> {
     Tick,
 }
 
+// how long the input thread waits for stdin before looking at `paused`
+const POLL_INTERVAL: i32 = 50;
+
 #[derive(Debug)]
 pub struct Events {
     pub rx: mpsc::Receiver<Event<Key>>,
+    paused: Arc<AtomicBool>,
+    // set by the input thread while it is not polling or reading stdin
+    parked: Arc<AtomicBool>,
 }
 
-impl Events {
-    pub fn new() -> Events {
-        Events::from_config(EventConfig::default())
+impl EventConfig {
+    pub fn new(tick_rate: Duration) -> EventConfig {
+        EventConfig { tick_rate }
     }
+}
 
-    fn from_config(config: EventConfig) -> Events {
+impl Events {
+    pub fn from_config(config: EventConfig) -> Events {
         let (tx, rx) = mpsc::channel();
 
+        let paused = Arc::new(AtomicBool::new(false));
+        let paused_cloned = paused.clone();
+        let parked = Arc::new(AtomicBool::new(false));
+        let parked_cloned = parked.clone();
         let tx_cloned = tx.clone();
         thread::spawn(move || {
-            let stdin = io::stdin();
-
-            for evt in stdin.keys() {
-                if let Ok(key) = evt {
-                    if let Err(err) = tx_cloned.send(Event::Input(key)) {
-                        eprintln!("{}", err);
-                        return;
-                    }
+            let mut stdin = io::stdin();
+            // at least the size of stdin's own buffer, so nothing is left
+            // behind in it where poll can't see it
+            let mut buf = [0u8; 8192];
+
+            loop {
+                if paused_cloned.load(Ordering::SeqCst) {
+                    parked_cloned.store(true, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(POLL_INTERVAL as u64));
+                    continue;
+                }
+                parked_cloned.store(false, Ordering::SeqCst);
+                if !stdin_ready() || paused_cloned.load(Ordering::SeqCst) {
+                    continue;
+                }
+
+                let len = match stdin.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(len) => len,
+                };
+                // typed for the program that started meanwhile; it is too
+                // late to hand them over, but they mustn't turn up here
+                if paused_cloned.load(Ordering::SeqCst) {
+                    continue;
+                }
+                let sent = parse_input(&buf[..len])
+                    .into_iter()
+                    .try_for_each(|key| tx_cloned.send(Event::Input(key)));
+                if let Err(err) = sent {
+                    eprintln!("{}", err);
+                    break;
                 }
             }
+            // nothing left to wait for in `pause`
+            parked_cloned.store(true, Ordering::SeqCst);
         });
      
         thread::spawn(move || loop {
@@ -55,6 +97,50 @@ impl Events {
 
         Events {
             rx,
+            paused,
+            parked,
+        }
+    }
+
+    // while a program runs in the terminal its input must not be read here;
+    // returns once the input thread has let go of stdin, which takes at most
+    // one poll interval
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+        while !self.parked.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(1));
         }
     }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+}
+
+fn stdin_ready() -> bool {
+    let mut fd = libc::pollfd { fd: libc::STDIN_FILENO, events: libc::POLLIN, revents: 0 };
+
+    unsafe { libc::poll(&mut fd, 1, POLL_INTERVAL) > 0 }
+}
+
+fn parse_input(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut iter = bytes.iter().map(|&byte| Ok(byte)).peekable();
+
+    while let Some(Ok(byte)) = iter.next() {
+        // a lone escape byte is the Esc key, not the start of a sequence
+        if byte == b'\x1b' && iter.peek().is_none() {
+            keys.push(Key::Esc);
+            continue;
+        }
+
+        let key = match term_event::parse_event(byte, &mut iter) {
+            Ok(term_event::Event::Key(key)) => Some(key),
+            Ok(term_event::Event::Unsupported(bytes)) => keymap::decode_sequence(&bytes),
+            _ => None,
+        };
+        keys.extend(key);
+    }
+
+    keys
 }
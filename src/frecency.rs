@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::paths;
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+
+// once the ranks add up to this much they are all scaled down, so old
+// directories eventually drop out
+const MAX_TOTAL_RANK: f64 = 10000.0;
+
+// visits kept in memory before the database is written out again
+const SAVE_EVERY: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Entry {
+    rank: f64,
+    last_access: u64,
+}
+
+// directories by how often and how recently they were visited, in the
+// `path|rank|time` format of z and early zoxide
+#[derive(Debug, Default)]
+pub struct Frecency {
+    entries: HashMap<PathBuf, Entry>,
+    file: Option<PathBuf>,
+    // visits since the last save
+    unsaved: usize,
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+impl Frecency {
+    pub fn default_path() -> Option<PathBuf> {
+        let mut path = paths::data_dir()?;
+        path.push("frecency");
+
+        Some(path)
+    }
+
+    pub fn load(file: PathBuf) -> Frecency {
+        let mut frecency = Frecency::default();
+
+        if let Ok(f) = File::open(&file) {
+            for line in BufReader::new(f).lines().map_while(Result::ok) {
+                let mut fields = line.rsplitn(3, '|');
+                let (last_access, rank, path) = match (fields.next(), fields.next(), fields.next()) {
+                    (Some(last_access), Some(rank), Some(path)) => (last_access, rank, path),
+                    _ => continue,
+                };
+                // `nan` and `inf` parse, but would poison every total
+                let rank = rank.parse::<f64>().ok().filter(|rank| rank.is_finite());
+                if let (Some(rank), Ok(last_access)) = (rank, last_access.parse()) {
+                    frecency.entries.insert(PathBuf::from(path), Entry { rank, last_access });
+                }
+            }
+        }
+
+        frecency.file = Some(file);
+        frecency
+    }
+
+    // written out every `SAVE_EVERY` visits; `save` writes the rest
+    pub fn add(&mut self, dir: &Path, now: u64) -> io::Result<()> {
+        self.add_rank(dir, 1.0, now);
+        self.age();
+        self.unsaved += 1;
+        if self.unsaved < SAVE_EVERY {
+            return Ok(());
+        }
+
+        self.save()
+    }
+
+    fn add_rank(&mut self, dir: &Path, rank: f64, now: u64) {
+        let entry = self.entries
+            .entry(dir.to_path_buf())
+            .or_insert(Entry { rank: 0.0, last_access: now });
+        entry.rank += rank;
+        entry.last_access = entry.last_access.max(now);
+    }
+
+    fn age(&mut self) {
+        let total: f64 = self.entries.values().map(|entry| entry.rank).sum();
+        if total <= MAX_TOTAL_RANK {
+            return;
+        }
+
+        let factor = 0.9 * MAX_TOTAL_RANK / total;
+        for entry in self.entries.values_mut() {
+            entry.rank *= factor;
+        }
+        self.entries.retain(|_, entry| entry.rank >= 1.0);
+    }
+
+    // `fragments` have to appear in the path in order, the last one in the
+    // final component; the best match comes first
+    pub fn query(&self, fragments: &[&str], now: u64) -> Vec<(PathBuf, f64)> {
+        let fragments: Vec<String> = fragments.iter().map(|f| f.to_lowercase()).collect();
+
+        let mut matches: Vec<(PathBuf, f64)> = self.entries
+            .iter()
+            .filter(|(path, _)| matches(path, &fragments))
+            .map(|(path, entry)| (path.clone(), score(entry, now)))
+            .collect();
+
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        matches
+    }
+
+    // best existing match other than `current`
+    pub fn find(&self, fragments: &[&str], current: &Path, now: u64) -> Option<PathBuf> {
+        self.query(fragments, now)
+            .into_iter()
+            .map(|(path, _)| path)
+            .find(|path| path != current && path.is_dir())
+    }
+
+    pub fn autojump_path() -> Option<PathBuf> {
+        let data_dir = paths::data_dir()?;
+        Some(data_dir.parent()?.join("autojump").join("autojump.txt"))
+    }
+
+    // the text file autojump keeps, one `weight<TAB>path` per line
+    pub fn import_autojump(&mut self, file: &Path, now: u64) -> io::Result<usize> {
+        let text = fs::read_to_string(file)?;
+        let entries = text.lines().filter_map(|line| {
+            let (weight, path) = line.split_once('\t')?;
+            let weight = weight.trim().parse::<f64>().ok().filter(|weight| weight.is_finite())?;
+            Some((weight, path))
+        });
+
+        self.import(entries, now)
+    }
+
+    // zoxide's own database is binary, so ask it for a scored listing
+    pub fn import_zoxide(&mut self, now: u64) -> io::Result<usize> {
+        let output = Command::new("zoxide").args(["query", "--list", "--score"]).output()?;
+        if !output.status.success() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "zoxide query failed"));
+        }
+
+        let text = String::from_utf8_lossy(&output.stdout);
+        let entries = text.lines().filter_map(|line| {
+            let (score, path) = line.trim_start().split_once(' ')?;
+            Some((score.parse::<f64>().ok().filter(|score| score.is_finite())?, path))
+        });
+
+        self.import(entries, now)
+    }
+
+    fn import<'a, I: Iterator<Item = (f64, &'a str)>>(&mut self, entries: I, now: u64)
+            -> io::Result<usize> {
+        let mut count = 0;
+        for (rank, path) in entries {
+            self.add_rank(Path::new(path), rank, now);
+            count += 1;
+        }
+
+        self.age();
+        self.save()?;
+        Ok(count)
+    }
+
+    pub fn save(&mut self) -> io::Result<()> {
+        self.unsaved = 0;
+        let file = match &self.file {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+
+        let mut text = String::new();
+        for (path, entry) in self.entries.iter() {
+            text.push_str(&format!("{}|{}|{}\n", path.to_string_lossy(), entry.rank, entry.last_access));
+        }
+
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(file, text)
+    }
+}
+
+fn score(entry: &Entry, now: u64) -> f64 {
+    let age = now.saturating_sub(entry.last_access);
+    let factor = match age {
+        _ if age < HOUR => 4.0,
+        _ if age < DAY => 2.0,
+        _ if age < WEEK => 0.5,
+        _ => 0.25,
+    };
+
+    entry.rank * factor
+}
+
+fn matches(path: &Path, fragments: &[String]) -> bool {
+    let path = path.to_string_lossy().to_lowercase();
+
+    let last = match fragments.last() {
+        Some(last) => last,
+        None => return true,
+    };
+    let name = path.rsplit('/').next().unwrap_or("");
+    if !name.contains(last.as_str()) {
+        return false;
+    }
+
+    let mut rest = path.as_str();
+    for fragment in fragments {
+        match rest.find(fragment.as_str()) {
+            Some(idx) => rest = &rest[idx + fragment.len()..],
+            None => return false,
+        }
+    }
+
+    true
+}
@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::shell;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JobStatus {
+    Running,
+    Finished { success: bool, summary: String },
+}
+
+#[derive(Debug)]
+struct JobState {
+    status: JobStatus,
+    output: String,
+    progress: Option<(usize, usize)>,
+}
+
+// handed to the work to report how far along it is
+#[derive(Clone, Debug)]
+pub struct Progress(Arc<Mutex<JobState>>);
+
+impl Progress {
+    pub fn set(&self, done: usize, total: usize) {
+        self.0.lock().unwrap().progress = Some((done, total));
+    }
+}
+
+// work running on its own thread; the state is shared with it
+#[derive(Debug)]
+pub struct Job {
+    pub id: usize,
+    pub title: String,
+    state: Arc<Mutex<JobState>>,
+    reported: bool,
+}
+
+impl Job {
+    pub fn status(&self) -> JobStatus {
+        self.state.lock().unwrap().status.clone()
+    }
+
+    pub fn output(&self) -> String {
+        self.state.lock().unwrap().output.clone()
+    }
+
+    // done out of total, once the work has said
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        self.state.lock().unwrap().progress
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Jobs {
+    jobs: Vec<Job>,
+    next_id: usize,
+}
+
+impl Jobs {
+    // `work` returns whether it succeeded, a one line summary and its output
+    pub fn spawn<F>(&mut self, title: String, work: F) -> usize
+            where F: FnOnce(&Progress) -> (bool, String, String) + Send + 'static {
+        self.next_id += 1;
+        let state = Arc::new(Mutex::new(JobState {
+            status: JobStatus::Running,
+            output: String::new(),
+            progress: None,
+        }));
+
+        let shared = state.clone();
+        thread::spawn(move || {
+            let (success, summary, output) = work(&Progress(shared.clone()));
+            let mut state = shared.lock().unwrap();
+            state.status = JobStatus::Finished { success, summary };
+            state.output = output;
+        });
+
+        self.jobs.push(Job { id: self.next_id, title, state, reported: false });
+        self.next_id
+    }
+
+    pub fn spawn_shell(&mut self, command: String, dir: PathBuf) -> usize {
+        let line = command.clone();
+        self.spawn(command, move |_| match shell::capture(&line, &dir) {
+            Ok((status, output)) => (status.success(), shell::describe(&status), output),
+            Err(err) => (false, err.to_string(), String::new()),
+        })
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&Job> {
+        self.jobs.get(idx)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Job> {
+        self.jobs.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    // jobs that finished since the last call
+    pub fn finished(&mut self) -> Vec<&Job> {
+        let mut finished = Vec::new();
+        for job in self.jobs.iter_mut() {
+            if !job.reported && job.status() != JobStatus::Running {
+                job.reported = true;
+                finished.push(&*job);
+            }
+        }
+
+        finished
+    }
+}
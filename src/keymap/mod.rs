@@ -0,0 +1,447 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use termion::event::Key;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Up,
+    Down,
+    Parent,
+    Open,
+    Command,
+    ToggleHidden,
+    Top,
+    Bottom,
+    HalfPageUp,
+    HalfPageDown,
+    PageUp,
+    PageDown,
+    Copy,
+    Cut,
+    Paste,
+    Delete,
+    ToggleLongListing,
+    SortName,
+    SortSize,
+    SortModified,
+    SortExtension,
+    ReverseSort,
+    ToggleMiller,
+    ToggleDual,
+    SwitchPane,
+    CopyToOther,
+    MoveToOther,
+    NewTab,
+    CloseTab,
+    NextTab,
+    PrevTab,
+    DuplicateTab,
+    Filter,
+    ToggleTree,
+    Back,
+    Forward,
+    Mark,
+    Jump,
+    Bookmarks,
+    Z,
+    ZPicker,
+    ZImport,
+    ToggleSelect,
+    ClearSelection,
+    OpenWith,
+    View,
+    Shell,
+    ShellBackground,
+    Jobs,
+    Subshell,
+    PreviewDown,
+    PreviewUp,
+    ToggleHex,
+    Compress,
+    Extract,
+}
+
+const ACTIONS: [(Action, &str); 56] = [
+    (Action::Quit, "quit"),
+    (Action::Up, "up"),
+    (Action::Down, "down"),
+    (Action::Parent, "parent"),
+    (Action::Open, "open"),
+    (Action::Command, "command"),
+    (Action::ToggleHidden, "toggle_hidden"),
+    (Action::Top, "top"),
+    (Action::Bottom, "bottom"),
+    (Action::HalfPageUp, "half_page_up"),
+    (Action::HalfPageDown, "half_page_down"),
+    (Action::PageUp, "page_up"),
+    (Action::PageDown, "page_down"),
+    (Action::Copy, "copy"),
+    (Action::Cut, "cut"),
+    (Action::Paste, "paste"),
+    (Action::Delete, "delete"),
+    (Action::ToggleLongListing, "toggle_long_listing"),
+    (Action::SortName, "sort_name"),
+    (Action::SortSize, "sort_size"),
+    (Action::SortModified, "sort_modified"),
+    (Action::SortExtension, "sort_extension"),
+    (Action::ReverseSort, "reverse_sort"),
+    (Action::ToggleMiller, "toggle_miller"),
+    (Action::ToggleDual, "toggle_dual"),
+    (Action::SwitchPane, "switch_pane"),
+    (Action::CopyToOther, "copy_to_other"),
+    (Action::MoveToOther, "move_to_other"),
+    (Action::NewTab, "new_tab"),
+    (Action::CloseTab, "close_tab"),
+    (Action::NextTab, "next_tab"),
+    (Action::PrevTab, "prev_tab"),
+    (Action::DuplicateTab, "duplicate_tab"),
+    (Action::Filter, "filter"),
+    (Action::ToggleTree, "toggle_tree"),
+    (Action::Back, "back"),
+    (Action::Forward, "forward"),
+    (Action::Mark, "mark"),
+    (Action::Jump, "jump"),
+    (Action::Bookmarks, "bookmarks"),
+    (Action::Z, "z"),
+    (Action::ZPicker, "z_picker"),
+    (Action::ZImport, "z_import"),
+    (Action::ToggleSelect, "toggle_select"),
+    (Action::ClearSelection, "clear_selection"),
+    (Action::OpenWith, "open_with"),
+    (Action::View, "view"),
+    (Action::Shell, "shell"),
+    (Action::ShellBackground, "shell_background"),
+    (Action::Jobs, "jobs"),
+    (Action::Subshell, "subshell"),
+    (Action::PreviewDown, "preview_down"),
+    (Action::PreviewUp, "preview_up"),
+    (Action::ToggleHex, "toggle_hex"),
+    (Action::Compress, "compress"),
+    (Action::Extract, "extract"),
+];
+
+impl Action {
+    // actions that take the rest of the command line, e.g. `:filter foo`;
+    // bound to a key they prompt for it or take the next key instead
+    pub fn takes_argument(&self) -> bool {
+        matches!(self, Action::Filter | Action::Mark | Action::Jump | Action::Z | Action::ZImport
+                 | Action::Shell | Action::ShellBackground | Action::Compress)
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Action, String> {
+        ACTIONS
+            .iter()
+            .find(|(_, action_name)| *action_name == name)
+            .map(|(action, _)| *action)
+            .ok_or_else(|| format!("unknown action `{}`", name))
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = ACTIONS
+            .iter()
+            .find(|(action, _)| action == self)
+            .map(|(_, name)| *name)
+            .unwrap_or("");
+
+        write!(f, "{}", name)
+    }
+}
+
+const DEFAULT_BINDINGS: [(&str, Action); 71] = [
+    ("q", Action::Quit),
+    ("<C-c>", Action::Quit),
+    ("k", Action::Up),
+    ("<Up>", Action::Up),
+    ("j", Action::Down),
+    ("<Down>", Action::Down),
+    ("h", Action::Parent),
+    ("<Left>", Action::Parent),
+    ("l", Action::Open),
+    ("<Right>", Action::Open),
+    ("<CR>", Action::Open),
+    (":", Action::Command),
+    (".", Action::ToggleHidden),
+    ("gg", Action::Top),
+    ("<Home>", Action::Top),
+    ("G", Action::Bottom),
+    ("<End>", Action::Bottom),
+    ("<C-u>", Action::HalfPageUp),
+    ("<C-d>", Action::HalfPageDown),
+    ("<PageUp>", Action::PageUp),
+    ("<C-b>", Action::PageUp),
+    ("<PageDown>", Action::PageDown),
+    ("<C-f>", Action::PageDown),
+    ("yy", Action::Copy),
+    ("dd", Action::Cut),
+    ("pp", Action::Paste),
+    ("dD", Action::Delete),
+    ("<Del>", Action::Delete),
+    ("<F8>", Action::Delete),
+    ("zh", Action::ToggleHidden),
+    ("zl", Action::ToggleLongListing),
+    ("on", Action::SortName),
+    ("os", Action::SortSize),
+    ("om", Action::SortModified),
+    ("oe", Action::SortExtension),
+    ("or", Action::ReverseSort),
+    ("zm", Action::ToggleMiller),
+    ("zd", Action::ToggleDual),
+    ("<Tab>", Action::SwitchPane),
+    ("<F5>", Action::CopyToOther),
+    ("<F6>", Action::MoveToOther),
+    ("gn", Action::NewTab),
+    ("<C-t>", Action::NewTab),
+    ("gc", Action::CloseTab),
+    ("<C-w>", Action::CloseTab),
+    ("gt", Action::NextTab),
+    ("gT", Action::PrevTab),
+    ("gd", Action::DuplicateTab),
+    ("zf", Action::Filter),
+    ("zt", Action::ToggleTree),
+    ("<A-Left>", Action::Back),
+    ("H", Action::Back),
+    ("<A-Right>", Action::Forward),
+    ("L", Action::Forward),
+    ("m", Action::Mark),
+    ("'", Action::Jump),
+    ("gb", Action::Bookmarks),
+    ("gz", Action::ZPicker),
+    ("<Space>", Action::ToggleSelect),
+    ("uv", Action::ClearSelection),
+    ("r", Action::OpenWith),
+    ("i", Action::View),
+    ("!", Action::Shell),
+    ("&", Action::ShellBackground),
+    ("gj", Action::Jobs),
+    ("S", Action::Subshell),
+    ("J", Action::PreviewDown),
+    ("K", Action::PreviewUp),
+    ("zx", Action::ToggleHex),
+    ("ac", Action::Compress),
+    ("ax", Action::Extract),
+];
+
+// termion has no keys for Alt-arrows, so they are carried as Alt with an
+// arrow character; see `decode_sequence`
+const ALT_ARROWS: [(&str, u8, char); 4] = [
+    ("up", b'A', '↑'),
+    ("down", b'B', '↓'),
+    ("right", b'C', '→'),
+    ("left", b'D', '←'),
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeyResult {
+    Pending,
+    Action(Action, Option<usize>),
+    Unbound,
+}
+
+#[derive(Clone, Debug)]
+pub struct KeyMap {
+    bindings: HashMap<Vec<Key>, Action>,
+}
+
+impl Default for KeyMap {
+    fn default() -> KeyMap {
+        let mut keymap = KeyMap { bindings: HashMap::new() };
+
+        for (keys, action) in DEFAULT_BINDINGS.iter() {
+            keymap.bind(parse_keys(keys).unwrap(), *action);
+        }
+
+        keymap
+    }
+}
+
+impl KeyMap {
+    pub fn bind(&mut self, keys: Vec<Key>, action: Action) {
+        self.bindings.insert(keys, action);
+    }
+
+    pub fn unbind(&mut self, keys: &[Key]) {
+        self.bindings.remove(keys);
+    }
+
+    pub fn action(&self, keys: &[Key]) -> Option<Action> {
+        self.bindings.get(keys).copied()
+    }
+
+    fn is_prefix(&self, keys: &[Key]) -> bool {
+        self.bindings
+            .keys()
+            .any(|bound| bound.len() > keys.len() && bound.starts_with(keys))
+    }
+
+    pub fn feed(&self, buffer: &mut KeyBuffer, key: Key) -> KeyResult {
+        if key == Key::Esc && !buffer.is_empty() {
+            buffer.clear();
+            return KeyResult::Pending;
+        }
+
+        if let Key::Char(c @ '0'..='9') = key {
+            if buffer.keys.is_empty() && (c != '0' || buffer.count.is_some()) {
+                let digit = c.to_digit(10).unwrap() as usize;
+                let count = buffer.count.unwrap_or(0);
+                buffer.count = Some(count.saturating_mul(10).saturating_add(digit));
+                return KeyResult::Pending;
+            }
+        }
+
+        buffer.keys.push(key);
+
+        if let Some(action) = self.action(&buffer.keys) {
+            let count = buffer.count;
+            buffer.clear();
+            return KeyResult::Action(action, count);
+        }
+
+        if self.is_prefix(&buffer.keys) {
+            return KeyResult::Pending;
+        }
+
+        buffer.clear();
+        KeyResult::Unbound
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct KeyBuffer {
+    keys: Vec<Key>,
+    count: Option<usize>,
+}
+
+impl KeyBuffer {
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty() && self.count.is_none()
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.count = None;
+    }
+}
+
+impl Display for KeyBuffer {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some(count) = self.count {
+            write!(f, "{}", count)?;
+        }
+        for key in self.keys.iter() {
+            match key {
+                Key::Char(c) => write!(f, "{}", c)?,
+                Key::Ctrl(c) => write!(f, "<C-{}>", c)?,
+                Key::Alt(c) => write!(f, "<A-{}>", c)?,
+                other => write!(f, "<{:?}>", other)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn parse_keys(keys: &str) -> Result<Vec<Key>, String> {
+    let mut parsed: Vec<Key> = Vec::new();
+    let mut rest = keys;
+
+    while let Some(c) = rest.chars().next() {
+        let token_len = match (c, rest.find('>')) {
+            ('<', Some(end)) if end > 1 => end + 1,
+            _ => c.len_utf8(),
+        };
+        parsed.push(parse_key(&rest[..token_len])
+            .map_err(|_| format!("invalid key `{}` in `{}`", &rest[..token_len], keys))?);
+        rest = &rest[token_len..];
+    }
+
+    if parsed.is_empty() {
+        return Err("empty key sequence".to_string());
+    }
+
+    Ok(parsed)
+}
+
+// vim-style notation: `q`, `<C-c>`, `<A-x>`, `<Up>`, `<PageDown>`, `<F5>`, ...
+pub fn parse_key(key: &str) -> Result<Key, String> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(Key::Char(c));
+    }
+
+    let inner = key
+        .strip_prefix('<')
+        .and_then(|k| k.strip_suffix('>'))
+        .ok_or_else(|| format!("invalid key `{}`", key))?;
+
+    if let Some((modifier, rest)) = inner.split_once('-') {
+        let mut rest_chars = rest.chars();
+        if let (Some(c), None) = (rest_chars.next(), rest_chars.next()) {
+            match modifier.to_ascii_uppercase().as_str() {
+                "C" => return Ok(Key::Ctrl(c.to_ascii_lowercase())),
+                "A" | "M" => return Ok(Key::Alt(c)),
+                _ => {}
+            }
+        }
+        if modifier.eq_ignore_ascii_case("A") || modifier.eq_ignore_ascii_case("M") {
+            if let Some((_, _, arrow)) = ALT_ARROWS
+                    .iter()
+                    .find(|(name, _, _)| rest.eq_ignore_ascii_case(name)) {
+                return Ok(Key::Alt(*arrow));
+            }
+        }
+        if modifier.eq_ignore_ascii_case("S") && rest.eq_ignore_ascii_case("Tab") {
+            return Ok(Key::BackTab);
+        }
+        return Err(format!("invalid key `{}`", key));
+    }
+
+    let parsed = match inner.to_ascii_lowercase().as_str() {
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "cr" | "enter" | "return" => Key::Char('\n'),
+        "esc" => Key::Esc,
+        "tab" => Key::Char('\t'),
+        "bs" | "backspace" => Key::Backspace,
+        "del" | "delete" => Key::Delete,
+        "insert" => Key::Insert,
+        "space" => Key::Char(' '),
+        "lt" => Key::Char('<'),
+        lower => {
+            match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                Some(n) if (1..=12).contains(&n) => Key::F(n),
+                _ => return Err(format!("invalid key `{}`", key)),
+            }
+        }
+    };
+
+    Ok(parsed)
+}
+
+// escape sequences termion reports as unsupported, e.g. `ESC [ 1 ; 3 D` for
+// Alt-Left in xterm
+pub fn decode_sequence(bytes: &[u8]) -> Option<Key> {
+    let last = match bytes {
+        [0x1b, b'[', b'1', b';', b'3', last] => *last,
+        _ => return None,
+    };
+
+    ALT_ARROWS
+        .iter()
+        .find(|(_, code, _)| *code == last)
+        .map(|(_, _, arrow)| Key::Alt(*arrow))
+}
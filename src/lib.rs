@@ -1,2 +1,20 @@
+pub mod archive;
+pub mod bookmarks;
+pub mod cli;
+pub mod compressed;
+pub mod config;
 pub mod entry;
+pub mod frecency;
+pub mod jobs;
 pub mod command_input;
+pub mod keymap;
+pub mod opener;
+pub mod pane;
+pub mod paths;
+pub mod preview;
+pub mod shell;
+pub mod tabs;
+pub mod theme;
+pub mod tree;
+pub mod vfs;
+pub mod view;
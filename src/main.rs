@@ -1,10 +1,12 @@
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
+use std::fs::File;
+use std::io::Write;
+use std::process::{self, Command, Stdio};
+use std::path::{Path, PathBuf};
 use std::{env, error, fs, io};
 
 use termion::event::Key;
-use termion::raw::IntoRawMode;
-use termion::screen::AlternateScreen;
+use termion::raw::{IntoRawMode, RawTerminal};
+use termion::screen::{self, AlternateScreen};
 
 use tui::Terminal;
 use tui::backend::TermionBackend;
@@ -14,7 +16,6 @@ use tui::layout::{
     Layout
 };
 use tui::style::{
-    Color,
     Modifier,
     Style
 };
@@ -24,230 +25,380 @@ use tui::text::{
 };
 use tui::widgets::{
     Block,
-    BorderType,
-    Borders, List,
-    ListItem,
-    ListState,
     Paragraph
 };
 
+mod archive;
+mod bookmarks;
+mod cli;
+mod compressed;
+mod config;
 mod entry;
 mod event;
+mod frecency;
+mod jobs;
 mod command_input;
+mod keymap;
+mod opener;
+mod pane;
+mod paths;
+mod preview;
+mod shell;
+mod tabs;
+mod theme;
+mod tree;
+mod vfs;
+mod view;
 
+use archive::Format;
+use bookmarks::Bookmarks;
+use cli::Args;
+use config::Config;
 use entry::file_data::FileData;
-use event::{Event, Events};
+use entry::listing::SortKey;
+use event::{Event, EventConfig, Events};
+use frecency::Frecency;
+use jobs::{JobStatus, Jobs};
+use command_input::history::CommandHistory;
 use command_input::input::{CommandHandler, InputMode};
+use keymap::{Action, KeyBuffer, KeyResult};
+use opener::{Launch, Opener, Rule};
+use pane::{DualPane, Pane};
+use tabs::Tabs;
+use theme::Theme;
+use view::ViewMode;
+use view::bookmarks::BookmarkPopup;
+use view::frecency::FrecencyPicker;
+use view::jobs::JobsPopup;
+use view::opener::OpenWithPopup;
+use view::output::OutputPane;
+
+type Term = Terminal<TermionBackend<AlternateScreen<File>>>;
+
+struct App {
+    config: Config,
+    tabs: Tabs,
+    command: CommandHandler,
+    bookmarks: Bookmarks,
+    popup: Option<BookmarkPopup>,
+    pending_mark: Option<Action>,
+    frecency: Frecency,
+    picker: Option<FrecencyPicker>,
+    last_dir: PathBuf,
+    list_height: usize,
+    // with --choosefile(s), whether several files can be picked
+    choose_multiple: Option<bool>,
+    chosen: Option<Vec<PathBuf>>,
+    opener: Opener,
+    open_with: Option<OpenWithPopup>,
+    // run before the next draw, where the terminal can be handed over
+    launch: Option<Launch>,
+    subshell: bool,
+    jobs: Jobs,
+    jobs_popup: Option<JobsPopup>,
+    // `:!` commands, whose output is shown once they finish
+    awaited: Vec<usize>,
+    output: Option<OutputPane>,
+    // shown in the command line until the next key
+    status: Option<String>,
+}
 
 fn main() -> Result<(), Box<dyn error::Error>> {
-    let events: Events = Events::new();
-    let mut command: CommandHandler = CommandHandler::default();
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("rufile: {}\n\n{}", err, cli::USAGE);
+            process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    let config = match Config::load(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("rufile: {}", err);
+            process::exit(1);
+        }
+    };
 
-    let mut path: PathBuf = env::current_dir().unwrap();
+    let opener = match Opener::default_path() {
+        Some(path) => Opener::load(&path),
+        None => Ok(Opener::default()),
+    };
+    let opener = match opener {
+        Ok(opener) => opener,
+        Err(err) => {
+            eprintln!("rufile: {}", err);
+            process::exit(1);
+        }
+    };
+
+    let events: Events = Events::from_config(EventConfig::new(config.tick_rate));
+    let mut command = CommandHandler::default();
+    if let Some(history_path) = CommandHistory::default_path() {
+        command.history = CommandHistory::load(history_path);
+    }
 
-    let stdout = io::stdout().into_raw_mode()?;
-    let stdout = AlternateScreen::from(stdout);
-    let backend = TermionBackend::new(stdout);
+    let session = Tabs::default_path();
+    let restored = match &session {
+        Some(path) if config.restore_tabs => Tabs::load(path, &config.listing),
+        _ => None,
+    };
+    let tabs = match restored {
+        Some(tabs) => tabs,
+        None => Tabs::new(DualPane::new(env::current_dir()?, config.listing.clone())),
+    };
+    let bookmarks = Bookmarks::default_path().map(Bookmarks::load).unwrap_or_default();
+    let frecency = Frecency::default_path().map(Frecency::load).unwrap_or_default();
+    let last_dir = tabs.active().active().path.clone();
+
+    let mut app = App {
+        config,
+        tabs,
+        command,
+        bookmarks,
+        popup: None,
+        pending_mark: None,
+        frecency,
+        picker: None,
+        last_dir,
+        list_height: 0,
+        choose_multiple: args.chooser().map(|(_, multiple)| multiple),
+        chosen: None,
+        opener,
+        open_with: None,
+        launch: None,
+        subshell: false,
+        jobs: Jobs::default(),
+        jobs_popup: None,
+        awaited: Vec::new(),
+        output: None,
+        status: None,
+    };
+
+    // draw on the terminal itself, stdout may be where chosen files go;
+    // raw mode is kept apart so it can be left while a program runs
+    let tty = termion::get_tty()?;
+    let raw = tty.try_clone()?.into_raw_mode()?;
+    let tty = AlternateScreen::from(tty);
+    let backend = TermionBackend::new(tty);
     let mut terminal = Terminal::new(backend)?;
     
-    let mut marked_file = ListState::default();
-    marked_file.select(Some(0));
+    let mut keys = KeyBuffer::default();
 
     loop {
+        if let Some(launch) = app.launch.take() {
+            let selected = app.tabs.active().active().selected_path();
+            if run_launch(&mut terminal, &raw, &events, &launch).is_err() {
+                app.command.input_mode = InputMode::Error;
+            }
+            keep_selection(&mut app, selected);
+        }
+        if app.subshell {
+            app.subshell = false;
+            let pane = app.tabs.active().active();
+            let (dir, selected) = (pane.path.clone(), pane.selected_path());
+            if suspend(&mut terminal, &raw, &events, || shell::subshell(&dir))?.is_err() {
+                app.command.input_mode = InputMode::Error;
+            }
+            keep_selection(&mut app, selected);
+        }
+
         terminal.draw(|f| {
+            let App {
+                config, tabs, command, bookmarks, popup, pending_mark, frecency, picker,
+                open_with, jobs, jobs_popup, output, status, list_height, ..
+            } = &mut app;
+
+            let titles = tabs.titles();
+            let tab_bar_height = if titles.len() > 1 { 1 } else { 0 };
+
             let chunks = Layout::default()
                 .horizontal_margin(1)
                 .direction(Direction::Vertical)
                 .constraints([
+                        Constraint::Length(tab_bar_height),
                         Constraint::Min(3),
                         Constraint::Length(1),
                     ].as_ref()
                 )
                 .split(f.size());
-            
-            let main_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints(
-                    [
-                        Constraint::Percentage(50),
-                        Constraint::Percentage(50),
-                    ].as_ref()
-                )
-                .split(chunks[0]);
-            
-            let right = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints(
-                    [
-                        Constraint::Length(12),
-                        Constraint::Length(6),
-                        Constraint::Length(2)
-                    ].as_ref()
-                )
-                .split(main_chunks[1]);
 
-            let (list, mut paragraphs) = 
-                render_files(&mut marked_file, &path);
-        
-            f.render_stateful_widget(list, main_chunks[0], &mut marked_file);
-            f.render_widget(paragraphs.remove(1), right[1]);
-            f.render_widget(paragraphs.remove(0), right[0]);
+            if titles.len() > 1 {
+                let tab_bar = view::render_tab_bar(titles, tabs.active_index(), &config.theme);
+                f.render_widget(tab_bar, chunks[0]);
+            }
+
+            let panes = tabs.active_mut();
+            let pane = panes.active_mut();
+            let (files, nodes) = pane.listing();
+            pane.sync_selection(&files);
 
-            let input_chunk = render_input_field(&command);
-            f.render_widget(input_chunk, chunks[1]);
+            *list_height = match config.layout.view {
+                ViewMode::Split => view::draw_split(f, chunks[1], pane, &files, &nodes, config),
+                ViewMode::Miller => {
+                    view::miller::draw_miller(f, chunks[1], pane, &files, &nodes, config)
+                }
+                ViewMode::Dual => view::dual::draw_dual(f, chunks[1], panes, &files, &nodes, config),
+            };
+
+            if let Some(popup) = popup {
+                view::bookmarks::draw_bookmarks(f, chunks[1], popup, bookmarks, &config.theme);
+            }
+            if let Some(picker) = picker {
+                view::frecency::draw_picker(f, chunks[1], picker, frecency, &config.theme);
+            }
+            if let Some(open_with) = open_with {
+                view::opener::draw_open_with(f, chunks[1], open_with, &config.theme);
+            }
+            if let Some(jobs_popup) = jobs_popup {
+                view::jobs::draw_jobs(f, chunks[1], jobs_popup, jobs, &config.theme);
+            }
+            if let Some(output) = output {
+                view::output::draw_output(f, chunks[1], output, &config.theme);
+            }
+
+            let input_chunk = render_input_field(command, &keys, *pending_mark, status.as_deref(),
+                                                 &config.theme);
+            f.render_widget(input_chunk, chunks[2]);
 
             match command.input_mode {
                 InputMode::Editing => {
                     f.set_cursor(
-                        chunks[1].x + command.input.len() as u16,
-                        chunks[1].y,
+                        chunks[2].x + command.cursor_column(),
+                        chunks[2].y,
                     )
                 }
                 _ => {}
             }
         })?;
         
+        let command = &mut app.command;
         match events.rx.recv()? {
+            Event::Input(input) if app.popup.is_some() => on_popup_key(&mut app, input),
+            Event::Input(input) if app.picker.is_some() => on_picker_key(&mut app, input),
+            Event::Input(input) if app.open_with.is_some() => on_open_with_key(&mut app, input),
+            Event::Input(input) if app.output.is_some() => on_output_key(&mut app, input),
+            Event::Input(input) if app.jobs_popup.is_some() => on_jobs_key(&mut app, input),
             Event::Input(input) => match command.input_mode {
-                InputMode::Normal | InputMode::Error => match input {
-                    Key::Char('q') | Key::Ctrl('c') => break,
-                    Key::Up => on_up_pressed(&path, &mut marked_file),
-                    Key::Down => on_down_pressed(&path, &mut marked_file),
-                    Key::Right => on_right_pressed(&mut path, &mut marked_file),
-                    Key::Left => on_left_pressed(&mut path, &mut marked_file),
-                    Key::Char(':') => {
-                        command.input.push(':');
-                        command.input_mode = InputMode::Editing;
+                InputMode::Normal | InputMode::Error => {
+                    app.status = None;
+                    // `m a` and `' a`: the key after a mark action names the mark
+                    if let Some(action) = app.pending_mark.take() {
+                        if let Key::Char(c) = input {
+                            handle_action(&mut app, action, None, Some(c.to_string()));
+                        }
+                        continue;
+                    }
+
+                    if let KeyResult::Action(action, count) = app.config.keymap.feed(&mut keys, input) {
+                        if handle_action(&mut app, action, count, None) {
+                            break;
+                        }
                     }
-                    _ => {}
                 }
                 InputMode::Editing => match input {
-                    Key::Char('\n') => call_command(&mut command, &path, &marked_file),
-                    Key::Char(c) => command.input.push(c),
-                    Key::Backspace => { command.input.pop(); }
-                    Key::Esc => {
-                        command.input.drain(..);
-                        command.input_mode = InputMode::Normal;
+                    Key::Char('\n') => {
+                        call_command(command, app.tabs.active().active());
+                        if let Some((action, count)) = command.take_action() {
+                            let argument = command.take_argument();
+                            if handle_action(&mut app, action, count, argument) {
+                                break;
+                            }
+                        }
                     }
+                    Key::Char(c) => command.insert(c),
+                    Key::Backspace => command.backspace(),
+                    Key::Delete | Key::Ctrl('d') => command.delete(),
+                    Key::Left | Key::Ctrl('b') => command.move_left(),
+                    Key::Right | Key::Ctrl('f') => command.move_right(),
+                    Key::Home | Key::Ctrl('a') => command.move_home(),
+                    Key::End | Key::Ctrl('e') => command.move_end(),
+                    Key::Ctrl('w') => command.delete_word(),
+                    Key::Ctrl('u') => command.kill_to_start(),
+                    Key::Ctrl('k') => command.kill_to_end(),
+                    Key::Up | Key::Ctrl('p') => command.history_prev(),
+                    Key::Down | Key::Ctrl('n') => command.history_next(),
+                    Key::Ctrl('r') => command.start_search(),
+                    Key::Esc | Key::Ctrl('c') => command.cancel(),
+                    _ => {}
+                }
+                InputMode::Search => match input {
+                    Key::Char('\n') => command.accept_search(),
+                    Key::Char(c) => command.search_push(c),
+                    Key::Backspace => command.search_pop(),
+                    Key::Ctrl('r') => command.search_older(),
+                    Key::Esc | Key::Ctrl('g') | Key::Ctrl('c') => command.cancel_search(),
                     _ => {}
                 }
             },
-            Event::Tick => {},
+            Event::Tick => report_jobs(&mut app),
         }
-    }
 
-    Ok(())
-}
-
-fn render_files<'a>(marked_file: &mut ListState, path: &PathBuf) 
-        -> (List<'a>, Vec<Paragraph<'a>>) {
-    let files = read_dir(&path).unwrap();
-
-    let file_list_view = render_file_list(path, &files);
-    
-    let mut selected_file: Option<&FileData>  = None;
-    
-    if marked_file.selected().is_none() {
-        if files.len() > 0 {
-            marked_file.select(Some(0));
-        }
-    }
-    
-    if let Some(idx) = marked_file.selected() {
-        selected_file = files.get(idx);
+        record_visit(&mut app);
     }
 
-    let mut paragraphs = vec!();
-    paragraphs.push(render_preview(selected_file));
-    paragraphs.push(render_info(selected_file));
-
-    (file_list_view, paragraphs)
-}
-
-fn render_file_list<'a>(path: &PathBuf, files: &Vec<FileData>) -> List<'a> {
-    let title = format!(" {} ", path.to_string_lossy());
-    
-    let files_block = Block::default()
-        .borders(Borders::ALL)
-        .style(Style::default().fg(Color::White))
-        .title(title)
-        .border_style(Style::default().fg(Color::Yellow))
-        .border_type(BorderType::Thick);
-
-    let items: Vec<_> = files
-        .iter()
-        .map(|file| {
-            let mut file_name = file.name.clone();
-            let mut file_color = Style::default();
-            if file.is_dir() {
-                file_name.push('/');
-                file_color = file_color.fg(Color::Blue);
-            }
-            ListItem::new(Spans::from(vec![Span::styled(
-                file_name,
-                file_color,
-            )]))
-        })
-        .collect();
-
-    let file_view = List::new(items)
-        .block(files_block)
-        .highlight_style(
-            Style::default()
-                .bg(Color::Yellow)
-                .fg(Color::Black)
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol(">");
-
-    file_view
-}
+    if let Some(path) = &session {
+        app.tabs.save(path).ok();
+    }
+    app.frecency.save().ok();
+    // leave the alternate screen before anything is printed
+    drop(terminal);
+    drop(raw);
 
-fn render_preview<'a>(selected_file: Option<&FileData>) -> Paragraph<'a> {
-    let mut preview = String::from("");
-    if let Some(file) = selected_file {
-        if let Ok(text) = file.preview() {
-            preview = text;
+    if let Some(file) = &args.choosedir {
+        let dir = app.tabs.active().active().path.to_string_lossy().into_owned();
+        write_output(file, &dir)?;
+    }
+    if let Some((file, _)) = args.chooser() {
+        let chosen = match app.chosen {
+            Some(paths) if !paths.is_empty() => paths,
+            _ => process::exit(1),
+        };
+        let mut text = String::new();
+        for path in chosen {
+            text.push_str(&path.to_string_lossy());
+            text.push('\n');
         }
+        write_output(file, &text)?;
     }
 
-    Paragraph::new(preview)
-        .style(Style::default().fg(Color::White))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::LightBlue))
-                .title(" Preview ")
-                .border_type(BorderType::Thick),
-        )
+    Ok(())
 }
 
-fn render_info<'a>(selected_file: Option<&FileData>) -> Paragraph<'a> {
-    let mut info = String::from("");
-    if let Some(file) = selected_file {
-        info = file.info();
+// `-` stands for stdout
+fn write_output(file: &Path, text: &str) -> io::Result<()> {
+    if file == Path::new("-") {
+        return io::stdout().write_all(text.as_bytes());
     }
 
-    Paragraph::new(info)
-        .style(Style::default().fg(Color::White))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Green))
-                .title(" Info ")
-                .border_type(BorderType::Thick),
-        )
+    fs::write(file, text)
 }
 
-fn render_input_field(command: &CommandHandler) -> Paragraph {
+fn render_input_field<'a>(command: &'a CommandHandler, keys: &KeyBuffer,
+                          pending_mark: Option<Action>, status: Option<&'a str>,
+                          theme: &Theme) -> Paragraph<'a> {
     let text = match command.input_mode {
         InputMode::Error => {
             Spans::from(vec![Span::styled("Invalid command", 
                         Style::default()
-                        .fg(Color::Red)
+                        .fg(theme.error)
                         .add_modifier(Modifier::REVERSED))
             ])
         },
+        InputMode::Search => {
+            Spans::from(format!("(reverse-i-search)`{}': {}",
+                command.search_query,
+                command.search_matched().unwrap_or("")))
+        },
+        InputMode::Normal if pending_mark.is_some() => {
+            Spans::from(format!("{}: ", pending_mark.unwrap()))
+        }
+        InputMode::Normal if !keys.is_empty() => Spans::from(keys.to_string()),
+        InputMode::Normal if status.is_some() => Spans::from(status.unwrap_or_default()),
         _ => Spans::from(command.input.as_ref())
     };
 
@@ -259,93 +410,627 @@ fn render_input_field(command: &CommandHandler) -> Paragraph {
         .block(Block::default())
 }
 
-fn read_dir(path: &PathBuf) -> Result<Vec<FileData>, io::Error> {
-    let mut files: Vec<FileData> = Vec::<FileData>::new();
+// hands the terminal to `run` and takes it back afterwards, with a full
+// redraw since the program may have left anything on the screen
+fn suspend<T, F: FnOnce() -> T>(terminal: &mut Term, raw: &RawTerminal<File>, events: &Events,
+                                 run: F) -> io::Result<T> {
+    events.pause();
+    terminal.show_cursor()?;
+    write!(terminal.backend_mut(), "{}", screen::ToMainScreen)?;
+    terminal.backend_mut().flush()?;
+    raw.suspend_raw_mode()?;
 
-    for entry in fs::read_dir(&path)? {
-        let entry = entry?;
-        
-        if let Ok(entry_data) = FileData::new(entry) {
-            files.push(entry_data);
+    let result = run();
+
+    raw.activate_raw_mode()?;
+    write!(terminal.backend_mut(), "{}", screen::ToAlternateScreen)?;
+    terminal.clear()?;
+    events.resume();
+
+    Ok(result)
+}
+
+fn run_launch(terminal: &mut Term, raw: &RawTerminal<File>, events: &Events,
+              launch: &Launch) -> io::Result<()> {
+    let status = if launch.terminal {
+        suspend(terminal, raw, events, || {
+            Command::new("sh").arg("-c").arg(&launch.command).status()
+        })??
+    } else {
+        // started as a background job of the shell, so it is not tied to
+        // rufile and never left as a zombie
+        Command::new("sh")
+            .arg("-c")
+            .arg(format!("({}) >/dev/null 2>&1 &", launch.command))
+            .stdin(Stdio::null())
+            .status()?
+    };
+
+    if !status.success() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "command failed"));
+    }
+    Ok(())
+}
+
+fn matching_rules(opener: &Opener, file: &FileData) -> Vec<Rule> {
+    let mime = opener::mime_type(file);
+
+    opener.matching(&file.name, &mime).into_iter().cloned().collect()
+}
+
+fn launch_rule(app: &mut App, file: &Path, rule: &Rule) {
+    let pane = app.tabs.active().active();
+    app.launch = Some(rule.launch(file, &pane.path, &pane.chosen_paths()));
+}
+
+fn on_right_pressed(pane: &mut Pane) {
+    if let Some(file) = pane.selected_file() {
+        if pane.tree.is_some() {
+            pane.expand();
+        } else if file.is_dir() || Format::detect(file.path()).is_some() {
+            // archives are browsed like directories
+            pane.enter_dir(&file.name).ok();
+        }
+    }
+}
+
+fn on_popup_key(app: &mut App, input: Key) {
+    let popup = match &mut app.popup {
+        Some(popup) => popup,
+        None => return,
+    };
+    let bookmarks = &mut app.bookmarks;
+
+    if let Some(mut new_name) = popup.rename.take() {
+        match input {
+            Key::Char('\n') => {
+                if let Some(name) = popup.selected(bookmarks).map(String::from) {
+                    if bookmarks.rename(&name, &new_name).is_err() {
+                        app.command.input_mode = InputMode::Error;
+                    }
+                }
+                return;
+            }
+            Key::Char(c) => new_name.push(c),
+            Key::Backspace => {
+                new_name.pop();
+            }
+            Key::Esc | Key::Ctrl('c') => return,
+            _ => {}
         }
+        popup.rename = Some(new_name);
+        return;
     }
 
-    Ok(files)
+    match input {
+        Key::Char('j') | Key::Down => popup.move_selection(bookmarks, 1),
+        Key::Char('k') | Key::Up => popup.move_selection(bookmarks, -1),
+        Key::Char('r') => {
+            popup.rename = popup.selected(bookmarks).map(String::from);
+        }
+        Key::Char('d') | Key::Delete => {
+            if let Some(name) = popup.selected(bookmarks).map(String::from) {
+                bookmarks.remove(&name).ok();
+                popup.move_selection(bookmarks, 0);
+            }
+        }
+        Key::Char('\n') | Key::Char('l') | Key::Right => {
+            if let Some(name) = popup.selected(bookmarks).map(String::from) {
+                jump_to(app, &name);
+            }
+            app.popup = None;
+        }
+        Key::Char('q') | Key::Esc | Key::Ctrl('c') => app.popup = None,
+        _ => {}
+    }
 }
 
-fn open_file(file_name: &str) {
-    Command::new("xdg-open")
-        .arg(file_name)
-        .stderr(Stdio::null())
-        .spawn().ok();
+fn on_open_with_key(app: &mut App, input: Key) {
+    let popup = match &mut app.open_with {
+        Some(popup) => popup,
+        None => return,
+    };
+
+    match input {
+        Key::Char('j') | Key::Down => popup.move_selection(1),
+        Key::Char('k') | Key::Up => popup.move_selection(-1),
+        Key::Char('\n') | Key::Char('l') | Key::Right => {
+            if let Some(popup) = app.open_with.take() {
+                if let Some(rule) = popup.selected() {
+                    launch_rule(app, &popup.file, rule);
+                }
+            }
+        }
+        Key::Char('q') | Key::Esc | Key::Ctrl('c') => app.open_with = None,
+        _ => {}
+    }
 }
 
-fn open_dir(path: &mut PathBuf, marked_file: &mut ListState) -> io::Result<()> {
-    let files = read_dir(path)?;
+fn on_output_key(app: &mut App, input: Key) {
+    let output = match &mut app.output {
+        Some(output) => output,
+        None => return,
+    };
+    let page = output.page();
 
-    if files.len() > 0 {
-        marked_file.select(Some(0));
-    } else {
-        marked_file.select(None);
+    match input {
+        Key::Char('j') | Key::Down => output.scroll_by(1),
+        Key::Char('k') | Key::Up => output.scroll_by(-1),
+        Key::Ctrl('d') => output.scroll_by(page / 2),
+        Key::Ctrl('u') => output.scroll_by(-page / 2),
+        Key::Char(' ') | Key::PageDown | Key::Ctrl('f') => output.scroll_by(page),
+        Key::PageUp | Key::Ctrl('b') => output.scroll_by(-page),
+        Key::Char('g') | Key::Home => output.scroll_by(isize::MIN / 2),
+        Key::Char('G') | Key::End => output.scroll_by(isize::MAX / 2),
+        Key::Char('q') | Key::Char('\n') | Key::Esc | Key::Ctrl('c') => app.output = None,
+        _ => {}
     }
+}
 
-    env::set_current_dir(&path)?;
+fn on_jobs_key(app: &mut App, input: Key) {
+    let popup = match &mut app.jobs_popup {
+        Some(popup) => popup,
+        None => return,
+    };
 
-    Ok(())
+    match input {
+        Key::Char('j') | Key::Down => popup.move_selection(&app.jobs, 1),
+        Key::Char('k') | Key::Up => popup.move_selection(&app.jobs, -1),
+        Key::Char('\n') | Key::Char('l') | Key::Right => {
+            let job = popup.state.selected().and_then(|idx| app.jobs.get(idx));
+            if let Some(job) = job {
+                let title = match job.status() {
+                    JobStatus::Running => format!("[{}] {} (running)", job.id, job.title),
+                    JobStatus::Finished { summary, .. } => {
+                        format!("[{}] {} ({})", job.id, job.title, summary)
+                    }
+                };
+                app.output = Some(OutputPane::new(title, job.output()));
+            }
+        }
+        Key::Char('q') | Key::Esc | Key::Ctrl('c') => app.jobs_popup = None,
+        _ => {}
+    }
 }
 
-fn on_right_pressed(path: &mut PathBuf, marked_file: &mut ListState) {
-    if let Ok(files) = read_dir(&path) {
-        if let Some(selected) = marked_file.selected() {
-            let file = &files[selected];
-            path.push(&file.name);
-
-            if file.is_file() {
-               open_file(&file.name);
-               path.pop();
-            } else if file.is_dir() {
-               open_dir(path, marked_file).ok();
+fn on_picker_key(app: &mut App, input: Key) {
+    let picker = match &mut app.picker {
+        Some(picker) => picker,
+        None => return,
+    };
+    let frecency = &app.frecency;
+
+    match input {
+        Key::Char('\n') => {
+            if let Some(path) = picker.selected(frecency) {
+                let pane = app.tabs.active_mut().active_mut();
+                if pane.change_dir(path).is_err() {
+                    app.command.input_mode = InputMode::Error;
+                }
             }
+            app.picker = None;
         }
+        Key::Char(c) => picker.push(c),
+        Key::Backspace => picker.pop(),
+        Key::Down | Key::Ctrl('n') | Key::Ctrl('j') => picker.move_selection(frecency, 1),
+        Key::Up | Key::Ctrl('p') | Key::Ctrl('k') => picker.move_selection(frecency, -1),
+        Key::Esc | Key::Ctrl('c') => app.picker = None,
+        _ => {}
     }
 }
 
-fn on_left_pressed(path: &mut PathBuf, marked_file: &mut ListState) {
-    path.pop();
-    env::set_current_dir(&path).ok();
-    marked_file.select(Some(0));
+// after files may have been added or removed, stay on the same entry
+fn keep_selection(app: &mut App, selected: Option<PathBuf>) {
+    if let Some(path) = selected {
+        app.tabs.active_mut().active_mut().select_path(&path);
+    }
 }
 
-fn on_up_pressed(path: &PathBuf, marked_file: &mut ListState) {
-    if let Some(selected) = marked_file.selected() {
-        let files_count = read_dir(&path).unwrap().len(); 
-        if selected > 0 {
-            marked_file.select(Some(selected - 1));
-        } else {
-            marked_file.select(Some(files_count - 1));
+fn report_jobs(app: &mut App) {
+    let mut finished = Vec::new();
+    for job in app.jobs.finished() {
+        let summary = match job.status() {
+            JobStatus::Finished { summary, .. } => summary,
+            JobStatus::Running => continue,
+        };
+        let message = format!("[{}] {}: {}", job.id, summary, job.title);
+        let output = job.output();
+
+        if let Some(idx) = app.awaited.iter().position(|&id| id == job.id) {
+            app.awaited.remove(idx);
+            if !output.is_empty() {
+                app.output = Some(OutputPane::new(message.clone(), output));
+            }
         }
+        finished.push(message);
+    }
+
+    if let Some(message) = finished.last() {
+        app.status = Some(message.clone());
+        let selected = app.tabs.active().active().selected_path();
+        keep_selection(app, selected);
     }
 }
 
-fn on_down_pressed(path: &PathBuf, marked_file: &mut ListState) {
-    if let Some(selected) = marked_file.selected() {
-        let files_count = read_dir(&path).unwrap().len(); 
-        if selected >= files_count - 1 {
-            marked_file.select(Some(0));
-        } else {
-            marked_file.select(Some(selected + 1));
+// `%f`, `%s` and `%d` are filled in as for opener rules. Both run as jobs;
+// the output of `:!` opens in the output pane when it is done
+fn run_shell(app: &mut App, line: &str, background: bool) {
+    let pane = app.tabs.active().active();
+    let file = pane.selected_path().unwrap_or_default();
+    let command = opener::expand(line, &file, &pane.path, &pane.chosen_paths());
+    let dir = pane.path.clone();
+
+    let id = app.jobs.spawn_shell(command, dir);
+    if background {
+        app.status = Some(format!("[{}] started: {}", id, line));
+    } else {
+        app.awaited.push(id);
+        app.status = Some(format!("[{}] running: {}", id, line));
+    }
+}
+
+// the marked files, or the one under the cursor, into `name` in the
+// current directory
+fn compress(app: &mut App, name: &str) {
+    let pane = app.tabs.active().active();
+    let paths = pane.chosen_paths();
+    let target = pane.path.join(name);
+    if paths.is_empty() || vfs::in_archive(&pane.path) || Format::from_name(name).is_none() {
+        app.command.input_mode = InputMode::Error;
+        return;
+    }
+
+    let title = format!("compress {}", name);
+    let id = app.jobs.spawn(title.clone(), move |progress| {
+        match archive::create(&paths, &target, |done, total| progress.set(done, total)) {
+            Ok(count) => {
+                let output = format!("{} entries written to {}", count, target.display());
+                (true, String::from("done"), output)
+            }
+            Err(err) => (false, err.to_string(), String::new()),
+        }
+    });
+    app.status = Some(format!("[{}] started: {}", id, title));
+}
+
+fn extract(app: &mut App) {
+    let pane = app.tabs.active().active();
+    let archive = pane
+        .selected_path()
+        .filter(|_| !vfs::in_archive(&pane.path))
+        .and_then(|path| Some((Format::detect(&path)?, path)));
+    let (format, path) = match archive {
+        Some(archive) => archive,
+        None => {
+            app.command.input_mode = InputMode::Error;
+            return;
+        }
+    };
+
+    let title = format!("extract {}", path.file_name().unwrap_or_default().to_string_lossy());
+    let id = app.jobs.spawn(title.clone(), move |progress| {
+        match archive::unpack(&path, format, |done, total| progress.set(done, total)) {
+            Ok(dest) => (true, String::from("done"), format!("extracted into {}", dest.display())),
+            Err(err) => (false, err.to_string(), String::new()),
+        }
+    });
+    app.status = Some(format!("[{}] started: {}", id, title));
+}
+
+// directories inside archives are left out, they can't be jumped to
+fn record_visit(app: &mut App) {
+    let path = &app.tabs.active().active().path;
+    if *path != app.last_dir {
+        app.last_dir = path.clone();
+        if !vfs::in_archive(path) {
+            app.frecency.add(path, frecency::now()).ok();
         }
     }
 }
 
-fn call_command(command: &mut CommandHandler, path: &PathBuf, marked_file: &ListState) {
-    match marked_file.selected() {
-        Some(selected) => {
-            let files = read_dir(&path);
-            if let Ok(files) = files {
-                command.exec(Some(&files[selected].name));
-            } 
+fn z_jump(app: &mut App, fragments: &str) {
+    let fragments: Vec<&str> = fragments.split_whitespace().collect();
+    let pane = app.tabs.active_mut().active_mut();
+
+    let jumped = app.frecency
+        .find(&fragments, &pane.path, frecency::now())
+        .map(|path| pane.change_dir(path).is_ok())
+        .unwrap_or(false);
+    if !jumped {
+        app.command.input_mode = InputMode::Error;
+    }
+}
+
+// `:z_import zoxide` or `:z_import autojump [file]`
+fn z_import(app: &mut App, argument: &str) {
+    let mut words = argument.split_whitespace();
+    let now = frecency::now();
+
+    let imported = match (words.next(), words.next()) {
+        (Some("zoxide"), None) => app.frecency.import_zoxide(now).ok(),
+        (Some("autojump"), file) => file
+            .map(PathBuf::from)
+            .or_else(Frecency::autojump_path)
+            .and_then(|file| app.frecency.import_autojump(&file, now).ok()),
+        _ => None,
+    };
+    if imported.is_none() {
+        app.command.input_mode = InputMode::Error;
+    }
+}
+
+// bookmark jumps go through the same path as entering a directory, so they
+// land in the history and restore the cursor
+fn jump_to(app: &mut App, name: &str) {
+    let pane = app.tabs.active_mut().active_mut();
+    let changed = match app.bookmarks.get(name) {
+        Some(path) => pane.change_dir(path.to_path_buf()).is_ok(),
+        None => false,
+    };
+
+    if !changed {
+        app.command.input_mode = InputMode::Error;
+    }
+}
+
+fn handle_action(app: &mut App, action: Action, count: Option<usize>,
+                 argument: Option<String>) -> bool {
+    match action {
+        Action::Mark | Action::Jump if argument.is_none() => {
+            app.pending_mark = Some(action);
+            return false;
+        }
+        Action::Mark => {
+            let name = argument.unwrap_or_default();
+            let path = app.tabs.active().active().path.clone();
+            if name.is_empty() || app.bookmarks.set(&name, &path).is_err() {
+                app.command.input_mode = InputMode::Error;
+            }
+            return false;
         }
-        None => command.exec(None) 
+        Action::Jump => {
+            jump_to(app, &argument.unwrap_or_default());
+            return false;
+        }
+        Action::Bookmarks => {
+            app.popup = Some(BookmarkPopup::default());
+            return false;
+        }
+        Action::Z | Action::ZPicker => {
+            match argument.filter(|fragments| !fragments.trim().is_empty()) {
+                Some(fragments) => z_jump(app, &fragments),
+                None => app.picker = Some(FrecencyPicker::default()),
+            }
+            return false;
+        }
+        Action::ZImport => {
+            match argument {
+                Some(argument) => z_import(app, &argument),
+                None => app.command.start_command(":z_import "),
+            }
+            return false;
+        }
+        // picking a file ends a --choosefile(s) session, directories are
+        // still entered
+        Action::Open if app.choose_multiple.is_some() => {
+            let pane = app.tabs.active().active();
+            let file = pane.selected_path().filter(|path| !path.is_dir());
+            if let Some(file) = file {
+                app.chosen = match app.choose_multiple {
+                    Some(true) => Some(pane.chosen_paths()),
+                    _ => Some(vec![file]),
+                };
+                return true;
+            }
+        }
+        Action::Shell | Action::ShellBackground => {
+            match argument {
+                Some(line) if !line.is_empty() => {
+                    run_shell(app, &line, action == Action::ShellBackground)
+                }
+                Some(_) => app.command.input_mode = InputMode::Error,
+                None if action == Action::Shell => app.command.start_command(":!"),
+                None => app.command.start_command(":&"),
+            }
+            return false;
+        }
+        Action::Jobs => {
+            app.jobs_popup = Some(JobsPopup::default());
+            return false;
+        }
+        Action::Compress => {
+            match argument {
+                Some(name) if !name.is_empty() => compress(app, &name),
+                Some(_) => app.command.input_mode = InputMode::Error,
+                None => app.command.start_command(":compress "),
+            }
+            return false;
+        }
+        Action::Extract => {
+            extract(app);
+            return false;
+        }
+        Action::Subshell => {
+            app.subshell = true;
+            return false;
+        }
+        Action::Open | Action::OpenWith | Action::View => {
+            let pane = app.tabs.active().active();
+            if vfs::in_archive(&pane.path) && pane.selected_file().is_some_and(|file| !file.is_dir()) {
+                // nothing to hand a program until it is copied out
+                app.command.input_mode = InputMode::Error;
+                return false;
+            }
+
+            let file = pane
+                .selected_file()
+                .filter(FileData::is_file)
+                .filter(|file| action != Action::Open || Format::detect(file.path()).is_none());
+            if let Some(file) = file {
+                let path = file.path().to_path_buf();
+                if action == Action::View {
+                    launch_rule(app, &path, &Rule::pager());
+                    return false;
+                }
+
+                let rules = matching_rules(&app.opener, &file);
+                match (action, rules.first()) {
+                    (_, None) => app.command.input_mode = InputMode::Error,
+                    (Action::Open, Some(rule)) => launch_rule(app, &path, rule),
+                    _ => app.open_with = Some(OpenWithPopup::new(path, rules)),
+                }
+                return false;
+            }
+        }
+        _ => {}
     }
+
+    let App { config, tabs, command, list_height, .. } = app;
+    let list_height = *list_height;
+    let dual = config.layout.view == ViewMode::Dual;
+
+    match action {
+        Action::NewTab => {
+            let path = tabs.active().active().path.clone();
+            tabs.open(path, config.listing.clone());
+            return false;
+        }
+        Action::CloseTab => {
+            tabs.close();
+            return false;
+        }
+        Action::NextTab => {
+            match count {
+                Some(n) => tabs.select(n.saturating_sub(1)),
+                None => tabs.select_next(),
+            }
+            return false;
+        }
+        Action::PrevTab => {
+            for _ in 0..count.unwrap_or(1) {
+                tabs.select_prev();
+            }
+            return false;
+        }
+        Action::DuplicateTab => {
+            tabs.duplicate();
+            return false;
+        }
+        _ => {}
+    }
+
+    let panes = tabs.active_mut();
+
+    match action {
+        Action::SwitchPane if dual => {
+            panes.switch().ok();
+            return false;
+        }
+        Action::CopyToOther | Action::MoveToOther if dual => {
+            let dir = panes.other().path.clone();
+            in_node_dir(panes.active(), |file_name| {
+                command.transfer(file_name, &dir, action == Action::MoveToOther)
+            });
+            return false;
+        }
+        _ => {}
+    }
+
+    let pane = panes.active_mut();
+    let steps = count.unwrap_or(1) as isize;
+    let page = list_height.max(1) as isize;
+
+    match action {
+        Action::Quit => return true,
+        Action::Up => pane.move_selection(-steps),
+        Action::Down => pane.move_selection(steps),
+        Action::HalfPageUp => pane.move_selection(-(page / 2).max(1) * steps),
+        Action::HalfPageDown => pane.move_selection((page / 2).max(1) * steps),
+        Action::PageUp => pane.move_selection(-page * steps),
+        Action::PageDown => pane.move_selection(page * steps),
+        Action::Top => pane.select_index(count.unwrap_or(1).saturating_sub(1)),
+        Action::Bottom => {
+            pane.select_index(count.map(|n| n.saturating_sub(1)).unwrap_or(usize::MAX))
+        }
+        Action::Open => on_right_pressed(pane),
+        Action::Parent => pane.collapse(),
+        Action::Command => command.start_editing(),
+        Action::ToggleHidden => {
+            pane.listing.show_hidden = !pane.listing.show_hidden;
+            pane.marked_file.select(None);
+        }
+        Action::Filter => match argument {
+            Some(filter) => {
+                pane.listing.filter = Some(filter).filter(|filter| !filter.is_empty());
+                pane.marked_file.select(None);
+            }
+            None => command.start_command(":filter "),
+        },
+        Action::Copy => run_operation(command, ":c", pane),
+        Action::Cut => run_operation(command, ":m", pane),
+        Action::Paste => run_operation(command, ":p", pane),
+        Action::Delete => run_operation(command, ":d", pane),
+        Action::ToggleLongListing => config.layout.long_listing = !config.layout.long_listing,
+        Action::SortName => pane.listing.sort = SortKey::Name,
+        Action::SortSize => pane.listing.sort = SortKey::Size,
+        Action::SortModified => pane.listing.sort = SortKey::Modified,
+        Action::SortExtension => pane.listing.sort = SortKey::Extension,
+        Action::ReverseSort => pane.listing.reverse = !pane.listing.reverse,
+        Action::ToggleMiller => {
+            config.layout.view = match config.layout.view {
+                ViewMode::Miller => ViewMode::Split,
+                _ => ViewMode::Miller,
+            };
+        }
+        Action::ToggleDual => {
+            config.layout.view = match config.layout.view {
+                ViewMode::Dual => ViewMode::Split,
+                _ => ViewMode::Dual,
+            };
+        }
+        Action::ToggleTree => pane.toggle_tree(),
+        Action::Back => pane.go_back(),
+        Action::Forward => pane.go_forward(),
+        Action::OpenWith | Action::View => {}
+        Action::Shell | Action::ShellBackground | Action::Jobs | Action::Subshell => {}
+        Action::Compress | Action::Extract => {}
+        Action::ToggleSelect => pane.toggle_select(),
+        Action::PreviewDown | Action::PreviewUp => {
+            // half the preview per step
+            let half = (pane.preview.height / 2).max(1) as isize;
+            let sign = if action == Action::PreviewUp { -1 } else { 1 };
+            pane.preview.scroll_by(sign * half * steps);
+        }
+        Action::ToggleHex => pane.preview.hex = !pane.preview.hex,
+        Action::ClearSelection => pane.selection.clear(),
+        Action::SwitchPane | Action::CopyToOther | Action::MoveToOther => {}
+        Action::NewTab | Action::CloseTab | Action::NextTab
+            | Action::PrevTab | Action::DuplicateTab => {}
+        Action::Mark | Action::Jump | Action::Bookmarks => {}
+        Action::Z | Action::ZPicker | Action::ZImport => {}
+    }
+
+    false
+}
+
+// operations work on names relative to the working directory, so run them
+// from the directory of the node under the cursor
+fn in_node_dir<F: FnOnce(Option<&str>)>(pane: &Pane, operation: F) {
+    let node = pane.selected_path();
+    let dir = node.as_deref().and_then(Path::parent).unwrap_or(&pane.path);
+    let file_name = node.as_deref()
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().into_owned());
+
+    vfs::set_current_dir(dir).ok();
+    operation(file_name.as_deref());
+    vfs::set_current_dir(&pane.path).ok();
+}
+
+fn run_operation(command: &mut CommandHandler, line: &str, pane: &Pane) {
+    in_node_dir(pane, |file_name| command.run(line, file_name));
+}
+
+fn call_command(command: &mut CommandHandler, pane: &Pane) {
+    in_node_dir(pane, |file_name| command.exec(file_name));
 }
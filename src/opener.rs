@@ -0,0 +1,247 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::str;
+
+use serde::Deserialize;
+
+use crate::config::ConfigError;
+use crate::entry::file_data::FileData;
+use crate::paths;
+
+// one entry of opener.toml; a rule without `mime` and `glob` matches
+// every file
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub mime: Option<String>,
+    pub glob: Option<String>,
+    pub command: String,
+    #[serde(default)]
+    pub terminal: bool,
+}
+
+// a command line ready to run through `sh -c`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Launch {
+    pub command: String,
+    pub terminal: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OpenerFile {
+    #[serde(default)]
+    rule: Vec<Rule>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Opener {
+    pub rules: Vec<Rule>,
+}
+
+// how much of a file is looked at to tell text from binary
+const SNIFF_SIZE: usize = 1024;
+
+const EDITOR: &str = "${VISUAL:-${EDITOR:-vi}}";
+const PAGER: &str = "${PAGER:-less}";
+
+impl Default for Opener {
+    fn default() -> Opener {
+        Opener {
+            rules: vec![
+                Rule {
+                    mime: Some(String::from("text/*")),
+                    glob: None,
+                    command: format!("{} %f", EDITOR),
+                    terminal: true,
+                },
+                Rule {
+                    mime: None,
+                    glob: None,
+                    command: String::from("xdg-open %f"),
+                    terminal: false,
+                },
+            ],
+        }
+    }
+}
+
+impl Opener {
+    pub fn default_path() -> Option<PathBuf> {
+        let mut path = paths::config_dir()?;
+        path.push("opener.toml");
+
+        Some(path)
+    }
+
+    // without a rules file text goes to the editor, everything else to
+    // xdg-open
+    pub fn load(path: &Path) -> Result<Opener, ConfigError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Opener::default()),
+            Err(err) => {
+                return Err(ConfigError { path: Some(path.to_path_buf()), message: err.to_string() });
+            }
+        };
+
+        Opener::parse(&content).map_err(|message| ConfigError {
+            path: Some(path.to_path_buf()),
+            message,
+        })
+    }
+
+    pub fn parse(content: &str) -> Result<Opener, String> {
+        let file: OpenerFile = toml::from_str(content).map_err(|err| err.to_string())?;
+
+        Ok(Opener { rules: file.rule })
+    }
+
+    // in the order of the file, the first one is what `l` runs
+    pub fn matching(&self, name: &str, mime: &str) -> Vec<&Rule> {
+        self.rules.iter().filter(|rule| rule.matches(name, mime)).collect()
+    }
+}
+
+impl Rule {
+    // view-only, whatever the type
+    pub fn pager() -> Rule {
+        Rule {
+            mime: None,
+            glob: None,
+            command: format!("{} %f", PAGER),
+            terminal: true,
+        }
+    }
+
+    pub fn matches(&self, name: &str, mime: &str) -> bool {
+        let mime_matches = match &self.mime {
+            Some(pattern) => glob_match(&pattern.to_lowercase(), &mime.to_lowercase()),
+            None => true,
+        };
+        let glob_matches = match &self.glob {
+            Some(pattern) => glob_match(pattern, name),
+            None => true,
+        };
+
+        mime_matches && glob_matches
+    }
+
+    pub fn launch(&self, file: &Path, dir: &Path, marked: &[PathBuf]) -> Launch {
+        Launch {
+            command: expand(&self.command, file, dir, marked),
+            terminal: self.terminal,
+        }
+    }
+}
+
+// the MIME type magic reports, or a guess from the contents when it
+// can't tell
+pub fn mime_type(file: &FileData) -> String {
+    match file.get_mime_type() {
+        Ok(mime) if mime.contains('/') => mime,
+        _ if is_text(file.path()) => String::from("text/plain"),
+        _ => String::from("application/octet-stream"),
+    }
+}
+
+// no NUL bytes and valid UTF-8, allowing for a character cut off at the end
+pub fn is_text(path: &Path) -> bool {
+    let mut head = Vec::with_capacity(SNIFF_SIZE);
+    let read = File::open(path)
+        .and_then(|file| file.take(SNIFF_SIZE as u64).read_to_end(&mut head));
+
+    read.is_ok() && looks_like_text(&head)
+}
+
+// the same test on bytes already read, looking only at the first of them
+pub fn looks_like_text(bytes: &[u8]) -> bool {
+    let head = &bytes[..bytes.len().min(SNIFF_SIZE)];
+    if head.contains(&0) {
+        return false;
+    }
+
+    match str::from_utf8(head) {
+        Ok(_) => true,
+        Err(err) => err.error_len().is_none(),
+    }
+}
+
+// `*` and `?` wildcards over the whole text
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // where the last `*` was and how much of the text it has taken
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+// `%f` the file, `%d` the directory, `%s` the marked files and `%%` a
+// literal percent sign, all quoted for the shell
+pub fn expand(command: &str, file: &Path, dir: &Path, marked: &[PathBuf]) -> String {
+    let mut expanded = String::new();
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            expanded.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('f') => expanded.push_str(&shell_quote(&file.to_string_lossy())),
+            Some('d') => expanded.push_str(&shell_quote(&dir.to_string_lossy())),
+            Some('s') => {
+                let quoted: Vec<String> = marked
+                    .iter()
+                    .map(|path| shell_quote(&path.to_string_lossy()))
+                    .collect();
+                expanded.push_str(&quoted.join(" "));
+            }
+            Some('%') => expanded.push('%'),
+            Some(other) => {
+                expanded.push('%');
+                expanded.push(other);
+            }
+            None => expanded.push('%'),
+        }
+    }
+
+    expanded
+}
+
+pub fn shell_quote(text: &str) -> String {
+    let safe = !text.is_empty() && text
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-+=.,:/@".contains(c));
+    if safe {
+        return text.to_string();
+    }
+
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
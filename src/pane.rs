@@ -0,0 +1,436 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::io;
+use std::time::SystemTime;
+
+use tui::text::Spans;
+use tui::widgets::ListState;
+
+use crate::archive::Entry;
+use crate::entry::file_data::FileData;
+use crate::entry::listing::{self, ListingOptions};
+use crate::preview::Kind;
+use crate::tree::{Tree, TreeNode};
+use crate::view::columns::TableView;
+use crate::vfs;
+
+// the entry that was selected the last time each directory was left
+#[derive(Clone, Debug, Default)]
+pub struct CursorMemory {
+    positions: HashMap<PathBuf, String>,
+}
+
+impl CursorMemory {
+    pub fn remember(&mut self, dir: &Path, name: &str) {
+        self.positions.insert(dir.to_path_buf(), name.to_string());
+    }
+
+    pub fn recall(&self, dir: &Path) -> Option<&str> {
+        self.positions.get(dir).map(String::as_str)
+    }
+}
+
+const HISTORY_SIZE: usize = 100;
+
+// directories visited before and after the current one, browser style
+#[derive(Clone, Debug, Default)]
+pub struct DirHistory {
+    back: Vec<PathBuf>,
+    forward: Vec<PathBuf>,
+}
+
+impl DirHistory {
+    pub fn push(&mut self, dir: PathBuf) {
+        if self.back.last() != Some(&dir) {
+            self.back.push(dir);
+        }
+        if self.back.len() > HISTORY_SIZE {
+            self.back.remove(0);
+        }
+        self.forward.clear();
+    }
+}
+
+// how far the preview of `path` is scrolled; another file starts at the top
+#[derive(Clone, Debug, Default)]
+pub struct PreviewState {
+    pub path: Option<PathBuf>,
+    pub scroll: usize,
+    pub height: usize,
+    pub hex: bool,
+    // when the file was last changed, as of when the caches below were filled
+    pub modified: Option<SystemTime>,
+    // what kind of preview the file gets, and its MIME type for the Info pane
+    pub kind: Option<Kind>,
+    pub mime: Option<String>,
+    // text highlighted as far down as it was asked for
+    pub text: Option<(usize, Vec<Spans<'static>>)>,
+    // the listing of an archive being previewed, read once per file
+    pub archive: Option<Vec<Entry>>,
+    // the start of a compressed file being previewed, decompressed once
+    pub decompressed: Option<Vec<u8>>,
+    // an image drawn for the pane size it was drawn at
+    pub image: Option<((usize, usize), Vec<Spans<'static>>)>,
+    // its dimensions and color type, for the Info pane
+    pub image_info: Option<String>,
+    // shown in the title next to "Preview"
+    pub header: Option<String>,
+}
+
+impl PreviewState {
+    pub fn follow(&mut self, path: Option<&Path>) {
+        if self.path.as_deref() != path {
+            self.path = path.map(Path::to_path_buf);
+            self.scroll = 0;
+            self.modified = None;
+            self.forget();
+        }
+    }
+
+    // the same file, changed since it was last shown
+    pub fn refresh(&mut self, modified: Option<SystemTime>) {
+        if self.modified != modified {
+            self.modified = modified;
+            self.forget();
+        }
+    }
+
+    fn forget(&mut self) {
+        self.kind = None;
+        self.mime = None;
+        self.text = None;
+        self.archive = None;
+        self.decompressed = None;
+        self.image = None;
+        self.image_info = None;
+    }
+
+    pub fn scroll_by(&mut self, offset: isize) {
+        self.scroll = (self.scroll as isize + offset).max(0) as usize;
+    }
+}
+
+#[derive(Clone)]
+pub struct Pane {
+    pub path: PathBuf,
+    pub listing: ListingOptions,
+    pub tree: Option<Tree>,
+    pub marked_file: ListState,
+    pub table: TableView,
+    pub cursor_memory: CursorMemory,
+    pub history: DirHistory,
+    pub selection: BTreeSet<PathBuf>,
+    pub preview: PreviewState,
+}
+
+impl Pane {
+    pub fn new(path: PathBuf, listing: ListingOptions) -> Pane {
+        let mut marked_file = ListState::default();
+        marked_file.select(Some(0));
+
+        Pane {
+            path,
+            listing,
+            tree: None,
+            marked_file,
+            table: TableView::default(),
+            cursor_memory: CursorMemory::default(),
+            history: DirHistory::default(),
+            selection: BTreeSet::new(),
+            preview: PreviewState::default(),
+        }
+    }
+
+    // in tree mode the visible nodes, flattened
+    pub fn files(&self) -> Vec<FileData> {
+        match &self.tree {
+            Some(_) => self.nodes().into_iter().map(|node| node.file).collect(),
+            None => listing::read_dir(&self.path, &self.listing).unwrap_or_default(),
+        }
+    }
+
+    // the files and, in tree mode, the nodes they are drawn as; the tree is
+    // walked once for both
+    pub fn listing(&self) -> (Vec<FileData>, Vec<TreeNode>) {
+        match &self.tree {
+            Some(_) => {
+                let nodes = self.nodes();
+                (nodes.iter().map(|node| node.file.clone()).collect(), nodes)
+            }
+            None => (self.files(), Vec::new()),
+        }
+    }
+
+    pub fn nodes(&self) -> Vec<TreeNode> {
+        match &self.tree {
+            Some(tree) => tree.nodes(&self.path, &self.listing),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn selected_file(&self) -> Option<FileData> {
+        let selected = self.marked_file.selected()?;
+
+        self.files().into_iter().nth(selected)
+    }
+
+    pub fn selected_name(&self) -> Option<String> {
+        self.selected_file().map(|file| file.name)
+    }
+
+    pub fn selected_path(&self) -> Option<PathBuf> {
+        self.selected_file().map(|file| file.path().to_path_buf())
+    }
+
+    // the marked files, or the one under the cursor when nothing is marked
+    pub fn chosen_paths(&self) -> Vec<PathBuf> {
+        if self.selection.is_empty() {
+            return self.selected_path().into_iter().collect();
+        }
+
+        self.selection.iter().cloned().collect()
+    }
+
+    pub fn toggle_select(&mut self) {
+        if let Some(path) = self.selected_path() {
+            if !self.selection.remove(&path) {
+                self.selection.insert(path);
+            }
+        }
+        // unlike `j`, marking stops at the last entry
+        if let Some(idx) = self.marked_file.selected() {
+            self.select_index(idx + 1);
+        }
+    }
+
+    pub fn sync_selection(&mut self, files: &[FileData]) {
+        match self.marked_file.selected() {
+            None if !files.is_empty() => self.marked_file.select(Some(0)),
+            Some(idx) if idx >= files.len() => {
+                self.marked_file.select(files.len().checked_sub(1));
+            }
+            _ => {}
+        }
+        self.table.select(self.marked_file.selected());
+    }
+
+    pub fn move_selection(&mut self, offset: isize) {
+        if let Some(selected) = self.marked_file.selected() {
+            let files_count = self.files().len();
+            if files_count == 0 {
+                return;
+            }
+
+            let last = files_count as isize - 1;
+            let target = selected as isize + offset;
+            let idx = match offset {
+                // single steps wrap around, larger jumps stop at the edges
+                -1 if target < 0 => last,
+                1 if target > last => 0,
+                _ => target.clamp(0, last),
+            };
+            self.marked_file.select(Some(idx as usize));
+        }
+    }
+
+    pub fn select_index(&mut self, idx: usize) {
+        let files_count = self.files().len();
+        if files_count > 0 {
+            self.marked_file.select(Some(idx.min(files_count - 1)));
+        }
+    }
+
+    // falls back to the top level entry containing `path` when it is hidden
+    // in a collapsed directory
+    pub fn select_path(&mut self, path: &Path) {
+        let files = self.files();
+        let top_level = path
+            .strip_prefix(&self.path)
+            .ok()
+            .and_then(|rest| rest.components().next())
+            .map(|first| self.path.join(first));
+
+        let idx = files
+            .iter()
+            .position(|file| file.path() == path)
+            .or_else(|| files.iter().position(|file| Some(file.path()) == top_level.as_deref()));
+        if idx.is_some() {
+            self.marked_file.select(idx);
+        }
+    }
+
+    pub fn select_file(&mut self, name: &str) {
+        let files = self.files();
+        self.select_name(&files, Some(name));
+    }
+
+    fn select_name(&mut self, files: &[FileData], name: Option<&str>) {
+        let idx = name
+            .and_then(|name| files.iter().position(|file| file.name == name))
+            .unwrap_or(0);
+
+        if files.is_empty() {
+            self.marked_file.select(None);
+        } else {
+            self.marked_file.select(Some(idx));
+        }
+    }
+
+    fn remember_selection(&mut self) {
+        if let Some(name) = self.selected_name() {
+            self.cursor_memory.remember(&self.path, &name);
+        }
+    }
+
+    pub fn change_dir(&mut self, dir: PathBuf) -> io::Result<()> {
+        let previous = self.path.clone();
+        self.visit(dir)?;
+
+        if previous != self.path {
+            self.history.push(previous);
+        }
+
+        Ok(())
+    }
+
+    pub fn go_back(&mut self) {
+        while let Some(dir) = self.history.back.pop() {
+            let current = self.path.clone();
+            // directories that are gone since are skipped
+            if dir != current && self.visit(dir).is_ok() {
+                self.history.forward.push(current);
+                break;
+            }
+        }
+    }
+
+    pub fn go_forward(&mut self) {
+        while let Some(dir) = self.history.forward.pop() {
+            let current = self.path.clone();
+            if dir != current && self.visit(dir).is_ok() {
+                self.history.back.push(current);
+                break;
+            }
+        }
+    }
+
+    fn visit(&mut self, dir: PathBuf) -> io::Result<()> {
+        vfs::read_dir(&dir)?;
+        vfs::set_current_dir(&dir)?;
+
+        self.remember_selection();
+        self.path = dir;
+
+        let files = self.files();
+        let name = self.cursor_memory.recall(&self.path).map(String::from);
+        self.select_name(&files, name.as_deref());
+
+        Ok(())
+    }
+
+    pub fn enter_dir(&mut self, name: &str) -> io::Result<()> {
+        self.change_dir(self.path.join(name))
+    }
+
+    pub fn go_parent(&mut self) {
+        let child = match self.path.file_name() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => return,
+        };
+        let parent = match self.path.parent() {
+            Some(parent) => parent.to_path_buf(),
+            None => return,
+        };
+
+        // coming back up, the directory we left is the natural selection
+        self.cursor_memory.remember(&parent, &child);
+        self.change_dir(parent).ok();
+    }
+
+    pub fn toggle_tree(&mut self) {
+        let selected = self.selected_path();
+        self.tree = match self.tree.take() {
+            Some(_) => None,
+            None => Some(Tree::default()),
+        };
+
+        if let Some(path) = selected {
+            self.select_path(&path);
+        }
+    }
+
+    pub fn expand(&mut self) {
+        let file = match self.selected_file() {
+            Some(file) if file.is_dir() => file,
+            _ => return,
+        };
+
+        if let Some(tree) = &mut self.tree {
+            tree.expand(file.path());
+        }
+    }
+
+    // collapses the node under the cursor, or the directory it is in
+    pub fn collapse(&mut self) {
+        let file = self.selected_file();
+        let (tree, file) = match (&mut self.tree, file) {
+            (Some(tree), Some(file)) => (tree, file),
+            _ => return self.go_parent(),
+        };
+
+        if file.is_dir() && tree.is_expanded(file.path()) {
+            tree.collapse(file.path());
+            return;
+        }
+
+        match file.path().parent().filter(|parent| *parent != self.path) {
+            Some(parent) => {
+                tree.collapse(parent);
+                self.select_path(parent);
+            }
+            None => self.go_parent(),
+        }
+    }
+}
+
+// the two panels of the commander view; the split and miller views only
+// ever show the active one
+#[derive(Clone)]
+pub struct DualPane {
+    pub panes: [Pane; 2],
+    pub active: usize,
+}
+
+impl DualPane {
+    pub fn new(path: PathBuf, listing: ListingOptions) -> DualPane {
+        DualPane {
+            panes: [Pane::new(path.clone(), listing.clone()), Pane::new(path, listing)],
+            active: 0,
+        }
+    }
+
+    pub fn active(&self) -> &Pane {
+        &self.panes[self.active]
+    }
+
+    pub fn active_mut(&mut self) -> &mut Pane {
+        &mut self.panes[self.active]
+    }
+
+    pub fn other(&self) -> &Pane {
+        &self.panes[1 - self.active]
+    }
+
+    pub fn other_mut(&mut self) -> &mut Pane {
+        &mut self.panes[1 - self.active]
+    }
+
+    // operations work relative to the working directory, so it follows
+    // the active panel
+    pub fn switch(&mut self) -> io::Result<()> {
+        vfs::set_current_dir(&self.other().path)?;
+        self.active = 1 - self.active;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use std::env;
+use std::path::PathBuf;
+
+fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    let mut path = match env::var_os(var) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let mut home = PathBuf::from(env::var_os("HOME")?);
+            home.push(fallback);
+            home
+        }
+    };
+    path.push("rufile");
+
+    Some(path)
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+pub fn data_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
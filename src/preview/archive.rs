@@ -0,0 +1,34 @@
+use chrono::{DateTime, Local};
+use humansize::{FileSize, file_size_opts};
+use tui::style::{Modifier, Style};
+use tui::text::{Span, Spans};
+
+use crate::archive::{self, Entry};
+
+// `12 entries, 3.4 MB`
+pub fn header(entries: &[Entry]) -> String {
+    let size = archive::total_size(entries).file_size(file_size_opts::DECIMAL).unwrap();
+    format!("{} entries, {}", entries.len(), size)
+}
+
+// `-rw-r--r--   1.2 KB  Mar  4 12:30  src/main.rs`
+pub fn format_entry(entry: &Entry) -> Spans<'static> {
+    let modified: DateTime<Local> = entry.modified.into();
+    let size = if entry.is_dir() {
+        String::from("-")
+    } else {
+        entry.size.file_size(file_size_opts::DECIMAL).unwrap()
+    };
+    let info = format!("{} {:>9}  {}  ", entry.mode_string(), size, modified.format("%b %e %H:%M"));
+
+    let (path, style) = if entry.is_dir() {
+        (format!("{}/", entry.path), Style::default().add_modifier(Modifier::BOLD))
+    } else {
+        (entry.path.clone(), Style::default())
+    };
+
+    Spans::from(vec![
+        Span::styled(info, Style::default().add_modifier(Modifier::DIM)),
+        Span::styled(path, style),
+    ])
+}
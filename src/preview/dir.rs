@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use humansize::{FileSize, file_size_opts};
+
+use crate::entry::file_data::FileData;
+use crate::vfs;
+
+// changes deeper down don't touch a directory's own mtime, so sizes are
+// added up again once they are this old
+const SIZE_TTL: Duration = Duration::from_secs(30);
+// huge trees are given up on rather than ground through
+const TIME_LIMIT: Duration = Duration::from_secs(5);
+const MAX_DEPTH: usize = 32;
+
+struct Cached {
+    modified: Option<SystemTime>,
+    added_up: Instant,
+    // `None` when adding up took too long
+    size: Option<u64>,
+}
+
+#[derive(Default)]
+struct Sizes {
+    cache: HashMap<PathBuf, Cached>,
+    // what the worker is adding up, or is about to
+    wanted: Option<PathBuf>,
+}
+
+// a single thread adds up one directory at a time; asking for another one
+// or calling `cancel` makes it drop what it is doing
+struct Worker {
+    sizes: Mutex<Sizes>,
+    wake: Condvar,
+}
+
+fn worker() -> &'static Worker {
+    static WORKER: OnceLock<Worker> = OnceLock::new();
+    WORKER.get_or_init(|| {
+        thread::spawn(work);
+        Worker { sizes: Mutex::new(Sizes::default()), wake: Condvar::new() }
+    })
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    pub dirs: usize,
+    pub files: usize,
+    pub size: Option<u64>,
+}
+
+impl Summary {
+    // `files` is the listing of `path` as it is shown
+    pub fn new(path: &Path, files: &[FileData]) -> Summary {
+        let dirs = files.iter().filter(|file| file.is_dir()).count();
+
+        Summary {
+            dirs,
+            files: files.len() - dirs,
+            size: total_size(path),
+        }
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} dirs, {} files", self.dirs, self.files)?;
+        if let Some(size) = self.size {
+            write!(f, ", {}", size.file_size(file_size_opts::DECIMAL).unwrap())?;
+        }
+
+        Ok(())
+    }
+}
+
+// the size of everything under `path`; the first call starts adding it up
+// in the background and returns `None` until that is done, a stale size
+// is returned while it is added up again
+pub fn total_size(path: &Path) -> Option<u64> {
+    if vfs::in_archive(path) {
+        return vfs::total_size(path);
+    }
+
+    let worker = worker();
+    let modified = modified(path);
+    let mut sizes = worker.sizes.lock().unwrap();
+    let (fresh, size) = match sizes.cache.get(path) {
+        Some(cached) => {
+            (cached.modified == modified && cached.added_up.elapsed() < SIZE_TTL, cached.size)
+        }
+        None => (false, None),
+    };
+
+    if !fresh && sizes.wanted.as_deref() != Some(path) {
+        sizes.wanted = Some(path.to_path_buf());
+        worker.wake.notify_one();
+    }
+
+    size
+}
+
+// the cursor left the directory being added up
+pub fn cancel() {
+    worker().sizes.lock().unwrap().wanted = None;
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn work() {
+    let worker = worker();
+
+    loop {
+        let path = {
+            let mut sizes = worker.sizes.lock().unwrap();
+            loop {
+                if let Some(path) = sizes.wanted.clone() {
+                    break path;
+                }
+                sizes = worker.wake.wait(sizes).unwrap();
+            }
+        };
+
+        let modified = modified(&path);
+        let cancelled = || worker.sizes.lock().unwrap().wanted.as_ref() != Some(&path);
+        let size = disk_usage(&path, Instant::now() + TIME_LIMIT, &cancelled);
+
+        let mut sizes = worker.sizes.lock().unwrap();
+        if sizes.wanted.as_ref() == Some(&path) {
+            sizes.wanted = None;
+            sizes.cache.insert(path, Cached { modified, added_up: Instant::now(), size });
+        }
+    }
+}
+
+// symlinks count as themselves, not what they point to, and other
+// filesystems mounted below `path` aren't entered; `None` once `deadline`
+// passes or the size is no longer wanted
+fn disk_usage(path: &Path, deadline: Instant, cancelled: &dyn Fn() -> bool) -> Option<u64> {
+    let dev = fs::metadata(path).ok()?.dev();
+    let mut dirs = vec![(path.to_path_buf(), 0)];
+    let mut total = 0;
+
+    while let Some((dir, depth)) = dirs.pop() {
+        if Instant::now() > deadline || cancelled() {
+            return None;
+        }
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.flatten() {
+            match entry.metadata() {
+                Ok(metadata) if metadata.is_dir() => {
+                    if metadata.dev() == dev && depth < MAX_DEPTH {
+                        dirs.push((entry.path(), depth + 1));
+                    }
+                }
+                Ok(metadata) => total += metadata.len(),
+                Err(_) => {}
+            }
+        }
+    }
+
+    Some(total)
+}
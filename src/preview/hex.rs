@@ -0,0 +1,67 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use tui::style::{Modifier, Style};
+use tui::text::{Span, Spans};
+
+pub const BYTES_PER_LINE: usize = 16;
+
+// `count` lines of an xxd style dump, starting at line `first`
+pub fn dump(path: &Path, first: usize, count: usize) -> io::Result<Vec<Spans<'static>>> {
+    let mut file = File::open(path)?;
+    let offset = (first * BYTES_PER_LINE) as u64;
+    file.seek(SeekFrom::Start(offset))?;
+
+    let mut bytes = Vec::with_capacity(count * BYTES_PER_LINE);
+    file.take((count * BYTES_PER_LINE) as u64).read_to_end(&mut bytes)?;
+
+    Ok(format_lines(offset, &bytes))
+}
+
+// the same for bytes already in memory
+pub fn dump_bytes(bytes: &[u8], first: usize, count: usize) -> Vec<Spans<'static>> {
+    let start = (first * BYTES_PER_LINE).min(bytes.len());
+    let end = ((first + count) * BYTES_PER_LINE).min(bytes.len());
+
+    format_lines(start as u64, &bytes[start..end])
+}
+
+fn format_lines(offset: u64, bytes: &[u8]) -> Vec<Spans<'static>> {
+    bytes
+        .chunks(BYTES_PER_LINE)
+        .enumerate()
+        .map(|(idx, chunk)| format_line(offset + (idx * BYTES_PER_LINE) as u64, chunk))
+        .collect()
+}
+
+// `00000010: 0201 0100 0000 0000  ........`
+pub fn format_line(offset: u64, bytes: &[u8]) -> Spans<'static> {
+    let mut hex = String::with_capacity(BYTES_PER_LINE * 5 / 2);
+    for idx in 0..BYTES_PER_LINE {
+        match bytes.get(idx) {
+            Some(byte) => hex.push_str(&format!("{:02x}", byte)),
+            None => hex.push_str("  "),
+        }
+        if idx % 2 == 1 {
+            hex.push(' ');
+        }
+    }
+
+    let ascii: String = bytes
+        .iter()
+        .map(|&byte| if (0x20..0x7f).contains(&byte) { byte as char } else { '.' })
+        .collect();
+
+    Spans::from(vec![
+        Span::styled(format!("{:08x}: ", offset), Style::default().add_modifier(Modifier::DIM)),
+        Span::raw(hex),
+        Span::raw(" "),
+        Span::raw(ascii),
+    ])
+}
+
+// lines in the dump of a file of `size` bytes
+pub fn line_count(size: u64) -> usize {
+    size.div_ceil(BYTES_PER_LINE as u64) as usize
+}
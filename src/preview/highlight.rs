@@ -0,0 +1,106 @@
+use std::path::Path;
+use std::sync::OnceLock;
+
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{self, Theme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use tui::style::{Color, Style};
+use tui::text::{Span, Spans};
+
+use crate::entry::file_data::FileData;
+use crate::opener;
+
+const THEME: &str = "base16-ocean.dark";
+const TAB: &str = "    ";
+
+struct Assets {
+    syntaxes: SyntaxSet,
+    theme: Theme,
+}
+
+// loading the bundled grammars takes a moment, so only the first preview
+// pays for it
+fn assets() -> &'static Assets {
+    static ASSETS: OnceLock<Assets> = OnceLock::new();
+
+    ASSETS.get_or_init(|| {
+        let mut themes = ThemeSet::load_defaults();
+        Assets {
+            syntaxes: SyntaxSet::load_defaults_newlines(),
+            theme: themes.themes.remove(THEME).unwrap_or_default(),
+        }
+    })
+}
+
+// by extension, then by the whole name (`Makefile`), the MIME type
+// (`text/x-python`) and finally a shebang or modeline in the first line
+pub fn find_syntax(file: &FileData, first_line: &str) -> &'static SyntaxReference {
+    syntax_for(&file.name, || Some(opener::mime_type(file)), first_line)
+}
+
+// the MIME type is only looked up when the name doesn't settle it
+fn syntax_for(name: &str, mime: impl FnOnce() -> Option<String>,
+              first_line: &str) -> &'static SyntaxReference {
+    let syntaxes = &assets().syntaxes;
+    let extension = Path::new(name).extension().map(|ext| ext.to_string_lossy());
+
+    extension
+        .and_then(|ext| syntaxes.find_syntax_by_extension(&ext))
+        .or_else(|| syntaxes.find_syntax_by_extension(name))
+        .or_else(|| {
+            let mime = mime()?;
+            let subtype = mime.split('/').nth(1)?;
+            syntaxes.find_syntax_by_token(subtype.trim_start_matches("x-"))
+        })
+        .or_else(|| syntaxes.find_syntax_by_first_line(first_line))
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text())
+}
+
+pub fn highlight(file: &FileData, lines: &[String]) -> Vec<Spans<'static>> {
+    highlight_with(find_syntax(file, first_line(lines)), lines)
+}
+
+// for text that isn't a file of its own, like a decompressed `notes.md.gz`
+// going by `notes.md`
+pub fn highlight_as(name: &str, lines: &[String]) -> Vec<Spans<'static>> {
+    highlight_with(syntax_for(name, || None, first_line(lines)), lines)
+}
+
+fn first_line(lines: &[String]) -> &str {
+    lines.first().map(String::as_str).unwrap_or("")
+}
+
+fn highlight_with(syntax: &'static SyntaxReference, lines: &[String]) -> Vec<Spans<'static>> {
+    let plain = || lines.iter().map(|line| Spans::from(line.replace('\t', TAB))).collect();
+
+    let assets = assets();
+    if syntax.name == assets.syntaxes.find_syntax_plain_text().name {
+        return plain();
+    }
+
+    let mut highlighter = HighlightLines::new(syntax, &assets.theme);
+    let mut highlighted = Vec::with_capacity(lines.len());
+    for line in lines {
+        // the grammars expect every line to end in a newline
+        let line = format!("{}\n", line);
+        let regions = match highlighter.highlight_line(&line, &assets.syntaxes) {
+            Ok(regions) => regions,
+            Err(_) => return plain(),
+        };
+
+        let spans: Vec<Span> = regions
+            .into_iter()
+            .map(|(style, text)| {
+                Span::styled(text.trim_end_matches('\n').replace('\t', TAB), convert(style))
+            })
+            .collect();
+        highlighted.push(Spans::from(spans));
+    }
+
+    highlighted
+}
+
+fn convert(style: highlighting::Style) -> Style {
+    let fg = style.foreground;
+    Style::default().fg(Color::Rgb(fg.r, fg.g, fg.b))
+}
@@ -0,0 +1,129 @@
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use image::imageops::FilterType;
+use image::{ColorType, DynamicImage, ImageFormat, ImageReader, Rgba};
+use tui::style::{Color, Style};
+use tui::text::{Span, Spans};
+
+use crate::entry::file_data::FileData;
+
+const FORMATS: [(&str, ImageFormat); 4] = [
+    ("image/png", ImageFormat::Png),
+    ("image/jpeg", ImageFormat::Jpeg),
+    ("image/gif", ImageFormat::Gif),
+    ("image/webp", ImageFormat::WebP),
+];
+
+pub fn detect(file: &FileData) -> Option<ImageFormat> {
+    let mime = file.get_mime_type().ok()?;
+    FORMATS.iter().find(|(name, _)| *name == mime).map(|&(_, format)| format)
+}
+
+pub fn decode(path: &Path, format: ImageFormat) -> Option<DynamicImage> {
+    let file = BufReader::new(File::open(path).ok()?);
+    ImageReader::with_format(file, format).decode().ok()
+}
+
+// `1920x1080, RGBA 8-bit`
+pub fn describe(image: &DynamicImage) -> String {
+    format!("{}x{}, {}", image.width(), image.height(), color_name(image.color()))
+}
+
+fn color_name(color: ColorType) -> String {
+    let channels = match color.channel_count() {
+        1 => "gray",
+        2 => "gray + alpha",
+        3 => "RGB",
+        _ => "RGBA",
+    };
+    let bits = color.bits_per_pixel() / u16::from(color.channel_count());
+
+    format!("{} {}-bit", channels, bits)
+}
+
+// whether the terminal says it takes 24-bit colors; otherwise the 256
+// color palette is used
+pub fn true_color() -> bool {
+    env::var("COLORTERM").is_ok_and(|value| value == "truecolor" || value == "24bit")
+}
+
+// `image` scaled to fit `width` columns and `height` rows of half blocks,
+// each row two pixels tall
+pub fn render(image: &DynamicImage, width: usize, height: usize,
+              true_color: bool) -> Vec<Spans<'static>> {
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+
+    let scaled = image.resize(width as u32, height as u32 * 2, FilterType::Triangle).to_rgba8();
+    (0..scaled.height())
+        .step_by(2)
+        .map(|y| {
+            let cells: Vec<Span> = (0..scaled.width())
+                .map(|x| {
+                    let bottom = (y + 1 < scaled.height()).then(|| scaled.get_pixel(x, y + 1));
+                    cell(scaled.get_pixel(x, y), bottom, true_color)
+                })
+                .collect();
+            Spans::from(cells)
+        })
+        .collect()
+}
+
+// the top pixel colors the `▀` and the bottom one the cell behind it;
+// transparent pixels leave the terminal's background showing
+fn cell(top: &Rgba<u8>, bottom: Option<&Rgba<u8>>, true_color: bool) -> Span<'static> {
+    let top = color(top, true_color);
+    let bottom = bottom.and_then(|pixel| color(pixel, true_color));
+
+    match (top, bottom) {
+        (Some(top), Some(bottom)) => Span::styled("▀", Style::default().fg(top).bg(bottom)),
+        (Some(top), None) => Span::styled("▀", Style::default().fg(top)),
+        (None, Some(bottom)) => Span::styled("▄", Style::default().fg(bottom)),
+        (None, None) => Span::raw(" "),
+    }
+}
+
+fn color(pixel: &Rgba<u8>, true_color: bool) -> Option<Color> {
+    let [red, green, blue, alpha] = pixel.0;
+    if alpha < 128 {
+        None
+    } else if true_color {
+        Some(Color::Rgb(red, green, blue))
+    } else {
+        Some(Color::Indexed(ansi256(red, green, blue)))
+    }
+}
+
+// the closest of the 6x6x6 color cube and the gray ramp in the 256 color
+// palette
+pub fn ansi256(red: u8, green: u8, blue: u8) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    let nearest = |value: u8| {
+        (0..LEVELS.len()).min_by_key(|&idx| LEVELS[idx].abs_diff(value)).unwrap_or(0)
+    };
+    let distance = |r: u8, g: u8, b: u8| {
+        [(r, red), (g, green), (b, blue)]
+            .iter()
+            .map(|&(a, b)| u32::from(a.abs_diff(b)).pow(2))
+            .sum::<u32>()
+    };
+
+    let (r, g, b) = (nearest(red), nearest(green), nearest(blue));
+    let cube = distance(LEVELS[r], LEVELS[g], LEVELS[b]);
+
+    // the ramp runs from 8 to 238 in steps of 10
+    let average = (u32::from(red) + u32::from(green) + u32::from(blue)) / 3;
+    let step = (average.saturating_sub(3) / 10).min(23);
+    let gray = (8 + step * 10) as u8;
+
+    if distance(gray, gray, gray) < cube {
+        232 + step as u8
+    } else {
+        (16 + 36 * r + 6 * g + b) as u8
+    }
+}
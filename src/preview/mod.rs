@@ -0,0 +1,174 @@
+use std::fs;
+
+use ::image::ImageFormat;
+use tui::text::Spans;
+
+use crate::archive::{self as archives, Format};
+use crate::compressed::{self, Codec};
+use crate::entry::file_data::FileData;
+use crate::opener;
+use crate::pane::PreviewState;
+
+pub mod archive;
+pub mod dir;
+pub mod hex;
+pub mod highlight;
+pub mod image;
+
+// how much of a compressed file is decompressed for its preview
+const COMPRESSED_PREVIEW: usize = 64 * 1024;
+
+// NUL bytes, invalid UTF-8 or libmagic seeing only `application/octet-stream`,
+// as it does for text full of control characters
+pub fn is_binary(file: &FileData) -> bool {
+    !opener::is_text(file.path())
+        || matches!(file.get_mime_type(), Ok(mime) if mime == "application/octet-stream")
+}
+
+// what sort of preview a file gets, found once per file and change to it
+#[derive(Clone, Debug)]
+pub enum Kind {
+    Archive(Format),
+    // the file's name without the codec's extension
+    Compressed(String, Codec),
+    Image(ImageFormat),
+    Binary,
+    Text,
+}
+
+impl Kind {
+    pub fn detect(file: &FileData) -> Kind {
+        if let Some(format) = Format::detect(file.path()) {
+            return Kind::Archive(format);
+        }
+        if let Some((name, codec)) = Codec::detect(file.path()) {
+            return Kind::Compressed(name, codec);
+        }
+        if let Some(format) = image::detect(file) {
+            return Kind::Image(format);
+        }
+
+        if is_binary(file) { Kind::Binary } else { Kind::Text }
+    }
+}
+
+// what the preview pane shows for `file`: `height` lines, `width` columns
+// wide, from where `state` is scrolled to
+pub fn file_preview(file: &FileData, state: &mut PreviewState,
+                    width: usize, height: usize) -> Vec<Spans<'static>> {
+    // through symlinks
+    let metadata = fs::metadata(file.path()).ok();
+    state.follow(Some(file.path()));
+    state.refresh(metadata.as_ref().and_then(|metadata| metadata.modified().ok()));
+    state.height = height;
+    state.header = None;
+    dir::cancel();
+
+    if state.kind.is_none() {
+        state.kind = Some(Kind::detect(file));
+        state.mime = file.get_mime_type().ok();
+    }
+    let kind = state.kind.clone().unwrap_or(Kind::Binary);
+
+    match kind {
+        _ if state.hex => {}
+        Kind::Archive(format) => return archive_preview(file, format, state, height),
+        Kind::Compressed(name, codec) => return compressed_preview(file, &name, codec, state, height),
+        Kind::Image(format) => return image_preview(file, format, state, width, height),
+        Kind::Binary => {}
+        Kind::Text => {
+            // highlighting has to start at the top to know where it is
+            return highlighted(state, height, |_, reach| {
+                let lines = file.preview(reach).unwrap_or_default();
+                highlight::highlight(file, &lines)
+            });
+        }
+    }
+
+    let size = metadata.map(|metadata| metadata.len()).unwrap_or(0);
+    let last = hex::line_count(size).saturating_sub(height);
+    state.scroll = state.scroll.min(last);
+    hex::dump(file.path(), state.scroll, height).unwrap_or_default()
+}
+
+// `height` lines from where `state` is scrolled to, out of the first `reach`
+// lines `highlight` gives back. They are kept, and only highlighted again
+// once scrolled past, as long as there could be more of them
+fn highlighted<F>(state: &mut PreviewState, height: usize, highlight: F) -> Vec<Spans<'static>>
+        where F: FnOnce(&PreviewState, usize) -> Vec<Spans<'static>> {
+    let reach = state.scroll + height;
+    let stale = match &state.text {
+        Some((wanted, lines)) => *wanted < reach && lines.len() >= *wanted,
+        None => true,
+    };
+    if stale {
+        let lines = highlight(state, reach);
+        state.text = Some((reach, lines));
+    }
+
+    let lines = state.text.as_ref().map(|(_, lines)| lines.as_slice()).unwrap_or_default();
+    state.scroll = state.scroll.min(lines.len().saturating_sub(height));
+    lines.iter().skip(state.scroll).take(height).cloned().collect()
+}
+
+// one line per entry under a header with the totals; an archive that
+// can't be read lists nothing
+fn archive_preview(file: &FileData, format: Format, state: &mut PreviewState,
+                   height: usize) -> Vec<Spans<'static>> {
+    if state.archive.is_none() {
+        state.archive = Some(archives::entries(file.path(), format).unwrap_or_default());
+    }
+    let entries = state.archive.as_deref().unwrap_or_default();
+
+    state.header = Some(archive::header(entries));
+    state.scroll = state.scroll.min(entries.len().saturating_sub(height));
+    entries.iter().skip(state.scroll).take(height).map(archive::format_entry).collect()
+}
+
+// the first `COMPRESSED_PREVIEW` bytes decompressed, then shown as text or
+// hex as if that was the file; `name` is the file without its extension.
+// Forcing hex dumps the compressed bytes instead, like it does for archives
+fn compressed_preview(file: &FileData, name: &str, codec: Codec, state: &mut PreviewState,
+                      height: usize) -> Vec<Spans<'static>> {
+    if state.decompressed.is_none() {
+        let bytes = compressed::head(file.path(), codec, COMPRESSED_PREVIEW).unwrap_or_default();
+        state.decompressed = Some(bytes);
+    }
+    let bytes = state.decompressed.as_deref().unwrap_or_default();
+    state.header = Some(codec.to_string());
+
+    if !opener::looks_like_text(bytes) {
+        let last = hex::line_count(bytes.len() as u64).saturating_sub(height);
+        state.scroll = state.scroll.min(last);
+        return hex::dump_bytes(bytes, state.scroll, height);
+    }
+
+    highlighted(state, height, |state, reach| {
+        let bytes = state.decompressed.as_deref().unwrap_or_default();
+        let lines: Vec<String> = String::from_utf8_lossy(bytes)
+            .lines()
+            .take(reach)
+            .map(String::from)
+            .collect();
+        highlight::highlight_as(name, &lines)
+    })
+}
+
+// decoded and scaled once per file and pane size; what the Info pane says
+// about the image is kept from the first time
+fn image_preview(file: &FileData, format: ImageFormat, state: &mut PreviewState,
+                 width: usize, height: usize) -> Vec<Spans<'static>> {
+    state.scroll = 0;
+    match &state.image {
+        Some((size, lines)) if *size == (width, height) => return lines.clone(),
+        _ => {}
+    }
+
+    let decoded = image::decode(file.path(), format);
+    state.image_info = decoded.as_ref().map(image::describe);
+    let lines = decoded
+        .map(|decoded| image::render(&decoded, width, height, image::true_color()))
+        .unwrap_or_default();
+    state.image = Some(((width, height), lines.clone()));
+    lines
+}
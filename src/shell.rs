@@ -0,0 +1,52 @@
+use std::env;
+use std::io;
+use std::os::unix::process::ExitStatusExt;
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+
+pub fn program() -> String {
+    env::var("SHELL")
+        .ok()
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or_else(|| String::from("/bin/sh"))
+}
+
+// runs `command` through the user's shell without a terminal; stdout and
+// stderr are collected in that order
+pub fn capture(command: &str, dir: &Path) -> io::Result<(ExitStatus, String)> {
+    let output = Command::new(program())
+        .arg("-c")
+        .arg(command)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .output()?;
+
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+
+    Ok((output.status, text))
+}
+
+// how many rufile subshells deep this process is
+pub fn level() -> u32 {
+    env::var("RUFILE_LEVEL")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(0)
+}
+
+// an interactive shell on the terminal; the caller has to give it up first
+pub fn subshell(dir: &Path) -> io::Result<ExitStatus> {
+    Command::new(program())
+        .current_dir(dir)
+        .env("RUFILE_LEVEL", (level() + 1).to_string())
+        .status()
+}
+
+pub fn describe(status: &ExitStatus) -> String {
+    match (status.code(), status.signal()) {
+        (Some(code), _) => format!("exit {}", code),
+        (None, Some(signal)) => format!("killed by signal {}", signal),
+        (None, None) => String::from("exited"),
+    }
+}
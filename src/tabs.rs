@@ -0,0 +1,176 @@
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::entry::listing::{ListingOptions, SortKey};
+use crate::pane::DualPane;
+use crate::paths;
+use crate::vfs;
+
+pub struct Tabs {
+    tabs: Vec<DualPane>,
+    active: usize,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct Session {
+    active: usize,
+    tab: Vec<SessionTab>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct SessionTab {
+    path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selected: Option<String>,
+    sort: String,
+    reverse: bool,
+    show_hidden: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<String>,
+}
+
+impl Tabs {
+    pub fn new(tab: DualPane) -> Tabs {
+        Tabs {
+            tabs: vec![tab],
+            active: 0,
+        }
+    }
+
+    pub fn default_path() -> Option<PathBuf> {
+        let mut path = paths::data_dir()?;
+        path.push("tabs.toml");
+
+        Some(path)
+    }
+
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    pub fn active(&self) -> &DualPane {
+        &self.tabs[self.active]
+    }
+
+    pub fn active_mut(&mut self) -> &mut DualPane {
+        &mut self.tabs[self.active]
+    }
+
+    pub fn titles(&self) -> Vec<String> {
+        self.tabs
+            .iter()
+            .map(|tab| {
+                let path = &tab.active().path;
+                path.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.to_string_lossy().into_owned())
+            })
+            .collect()
+    }
+
+    pub fn open(&mut self, path: PathBuf, listing: ListingOptions) {
+        self.tabs.insert(self.active + 1, DualPane::new(path, listing));
+        self.select(self.active + 1);
+    }
+
+    pub fn duplicate(&mut self) {
+        let tab = self.active().clone();
+        self.tabs.insert(self.active + 1, tab);
+        self.select(self.active + 1);
+    }
+
+    // the last tab is never closed
+    pub fn close(&mut self) -> bool {
+        if self.tabs.len() == 1 {
+            return false;
+        }
+
+        self.tabs.remove(self.active);
+        self.select(self.active.min(self.tabs.len() - 1));
+        true
+    }
+
+    pub fn select_next(&mut self) {
+        self.select((self.active + 1) % self.tabs.len());
+    }
+
+    pub fn select_prev(&mut self) {
+        self.select((self.active + self.tabs.len() - 1) % self.tabs.len());
+    }
+
+    pub fn select(&mut self, idx: usize) {
+        if idx < self.tabs.len() {
+            self.active = idx;
+            vfs::set_current_dir(&self.active().active().path).ok();
+        }
+    }
+
+    pub fn save(&self, file: &Path) -> io::Result<()> {
+        let session = Session {
+            active: self.active,
+            tab: self.tabs
+                .iter()
+                .map(|tab| {
+                    let pane = tab.active();
+                    SessionTab {
+                        path: pane.path.clone(),
+                        selected: pane.selected_name(),
+                        sort: pane.listing.sort.to_string(),
+                        reverse: pane.listing.reverse,
+                        show_hidden: pane.listing.show_hidden,
+                        filter: pane.listing.filter.clone(),
+                    }
+                })
+                .collect(),
+        };
+
+        let text = toml::to_string(&session)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(file, text)
+    }
+
+    // tabs whose directory is gone are dropped
+    pub fn load(file: &Path, listing: &ListingOptions) -> Option<Tabs> {
+        let text = fs::read_to_string(file).ok()?;
+        let session: Session = toml::from_str(&text).ok()?;
+
+        let mut active = 0;
+        let mut tabs = Vec::new();
+        for (idx, saved) in session.tab.into_iter().enumerate() {
+            if !saved.path.is_dir() {
+                continue;
+            }
+            if idx <= session.active {
+                active = tabs.len();
+            }
+
+            let listing = ListingOptions {
+                show_hidden: saved.show_hidden,
+                sort: saved.sort.parse().unwrap_or(SortKey::Name),
+                reverse: saved.reverse,
+                filter: saved.filter,
+                ..listing.clone()
+            };
+            let mut tab = DualPane::new(saved.path, listing);
+            if let Some(name) = saved.selected {
+                tab.panes[0].select_file(&name);
+            }
+            tabs.push(tab);
+        }
+
+        if tabs.is_empty() {
+            return None;
+        }
+
+        let mut tabs = Tabs { tabs, active: 0 };
+        tabs.select(active);
+        Some(tabs)
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::env;
+
+use tui::style::{Color, Modifier, Style};
+
+use super::Theme;
+use crate::entry::file_data::FileData;
+use crate::entry::type_parser::FileType;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LsColors {
+    kinds: HashMap<String, String>,
+    suffixes: Vec<(String, Style)>,
+}
+
+impl LsColors {
+    pub fn from_env() -> Option<LsColors> {
+        let value = env::var("LS_COLORS").ok()?;
+        if value.is_empty() {
+            return None;
+        }
+
+        Some(LsColors::parse(&value))
+    }
+
+    // `di=01;34:ln=01;36:*.tar=01;31:...` as exported by dircolors(1)
+    pub fn parse(ls_colors: &str) -> LsColors {
+        let mut colors = LsColors::default();
+
+        for entry in ls_colors.split(':') {
+            if let Some((key, codes)) = entry.split_once('=') {
+                colors.insert(key, codes);
+            }
+        }
+
+        colors
+    }
+
+    // the dircolors(1) database format: `DIR 01;34`, `.tar 01;31`, `*~ 00;90`
+    pub fn parse_dircolors(database: &str) -> LsColors {
+        let mut colors = LsColors::default();
+
+        for line in database.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let mut words = line.split_whitespace();
+            let (keyword, codes) = match (words.next(), words.next()) {
+                (Some(keyword), Some(codes)) => (keyword, codes),
+                _ => continue,
+            };
+
+            if keyword.starts_with('.') {
+                colors.insert(&format!("*{}", keyword), codes);
+                continue;
+            }
+            if keyword.starts_with('*') {
+                colors.insert(keyword, codes);
+                continue;
+            }
+
+            let key = match keyword.to_ascii_uppercase().as_str() {
+                "NORMAL" | "NORM" => "no",
+                "FILE" => "fi",
+                "RESET" => "rs",
+                "DIR" => "di",
+                "LINK" | "LNK" | "SYMLINK" => "ln",
+                "ORPHAN" => "or",
+                "MISSING" => "mi",
+                "FIFO" | "PIPE" => "pi",
+                "SOCK" => "so",
+                "DOOR" => "do",
+                "BLK" | "BLOCK" => "bd",
+                "CHR" | "CHAR" => "cd",
+                "EXEC" => "ex",
+                "SETUID" => "su",
+                "SETGID" => "sg",
+                "STICKY" => "st",
+                "OTHER_WRITABLE" | "OWR" => "ow",
+                "STICKY_OTHER_WRITABLE" | "OWT" => "tw",
+                "MULTIHARDLINK" => "mh",
+                _ => continue, // TERM, COLOR, OPTIONS, EIGHTBIT, CAPABILITY
+            };
+            colors.insert(key, codes);
+        }
+
+        colors
+    }
+
+    fn insert(&mut self, key: &str, codes: &str) {
+        match key.strip_prefix('*') {
+            Some(suffix) => self.suffixes.push((suffix.to_lowercase(), parse_sgr(codes))),
+            None => {
+                self.kinds.insert(key.to_string(), codes.to_string());
+            }
+        }
+    }
+
+    // nothing loaded from LS_COLORS or a dircolors database
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty() && self.suffixes.is_empty()
+    }
+
+    pub fn kind_style(&self, kind: &str) -> Option<Style> {
+        self.kinds.get(kind).map(|codes| parse_sgr(codes))
+    }
+
+    // later entries win, the same way ls(1) treats duplicates
+    pub fn suffix_style(&self, name: &str) -> Option<Style> {
+        let name = name.to_lowercase();
+
+        self.suffixes
+            .iter()
+            .rev()
+            .find(|(suffix, _)| name.ends_with(suffix.as_str()))
+            .map(|(_, style)| *style)
+    }
+
+    // the theme's colors only stand in when nothing was loaded; like ls(1),
+    // a kind missing from LS_COLORS isn't colored
+    pub fn style_for(&self, file: &FileData, theme: &Theme) -> Style {
+        let kind = match file.file_type() {
+            FileType::LNK => match file.target_type() {
+                None => "or",
+                Some(target) if self.kinds.get("ln").map(String::as_str) == Some("target") => {
+                    self.type_kind(target, file)
+                }
+                Some(_) => "ln",
+            },
+            file_type => self.type_kind(file_type, file),
+        };
+
+        if self.is_empty() {
+            return theme.kind_style(kind);
+        }
+        if kind == "fi" {
+            if let Some(style) = self.suffix_style(&file.name) {
+                return style;
+            }
+        }
+
+        self.kind_style(kind)
+            .or_else(|| if kind == "or" { self.kind_style("ln") } else { None })
+            .or_else(|| self.kind_style("no"))
+            .unwrap_or_default()
+    }
+
+    // the special kinds only count when they have a color, the same order
+    // of precedence as in ls(1)
+    fn type_kind(&self, file_type: FileType, file: &FileData) -> &'static str {
+        let colored = |kind: &str| {
+            self.kinds.contains_key(kind) || (self.is_empty() && kind == "ex")
+        };
+        let mode = file.mode();
+        let (setuid, setgid, sticky) = (mode & 0o4000 != 0, mode & 0o2000 != 0, mode & 0o1000 != 0);
+        let other_writable = mode & 0o002 != 0;
+
+        match file_type {
+            FileType::DIR if sticky && other_writable && colored("tw") => "tw",
+            FileType::DIR if other_writable && colored("ow") => "ow",
+            FileType::DIR if sticky && colored("st") => "st",
+            FileType::DIR => "di",
+            FileType::LNK => "ln",
+            FileType::FIFO => "pi",
+            FileType::SOCK => "so",
+            FileType::BLK => "bd",
+            FileType::CHR => "cd",
+            FileType::REG if setuid && colored("su") => "su",
+            FileType::REG if setgid && colored("sg") => "sg",
+            FileType::REG if file.is_executable() && colored("ex") => "ex",
+            FileType::REG if file.links() > 1 && colored("mh") => "mh",
+            _ => "fi",
+        }
+    }
+}
+
+pub fn parse_sgr(codes: &str) -> Style {
+    let mut style = Style::default();
+    let mut codes = codes
+        .split(';')
+        .map(|code| code.parse::<u8>().unwrap_or(0));
+
+    while let Some(code) = codes.next() {
+        style = match code {
+            0 => Style::default(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 => style.add_modifier(Modifier::SLOW_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            8 => style.add_modifier(Modifier::HIDDEN),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(ansi_color(code - 30, false)),
+            40..=47 => style.bg(ansi_color(code - 40, false)),
+            90..=97 => style.fg(ansi_color(code - 90, true)),
+            100..=107 => style.bg(ansi_color(code - 100, true)),
+            38 | 48 => {
+                let color = match codes.next() {
+                    Some(5) => codes.next().map(Color::Indexed),
+                    Some(2) => match (codes.next(), codes.next(), codes.next()) {
+                        (Some(r), Some(g), Some(b)) => Some(Color::Rgb(r, g, b)),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                match (code, color) {
+                    (38, Some(color)) => style.fg(color),
+                    (48, Some(color)) => style.bg(color),
+                    _ => style,
+                }
+            }
+            39 => style.fg(Color::Reset),
+            49 => style.bg(Color::Reset),
+            _ => style,
+        };
+    }
+
+    style
+}
+
+fn ansi_color(idx: u8, bright: bool) -> Color {
+    match (idx, bright) {
+        (0, false) => Color::Black,
+        (1, false) => Color::Red,
+        (2, false) => Color::Green,
+        (3, false) => Color::Yellow,
+        (4, false) => Color::Blue,
+        (5, false) => Color::Magenta,
+        (6, false) => Color::Cyan,
+        (7, false) => Color::Gray,
+        (0, true) => Color::DarkGray,
+        (1, true) => Color::LightRed,
+        (2, true) => Color::LightGreen,
+        (3, true) => Color::LightYellow,
+        (4, true) => Color::LightBlue,
+        (5, true) => Color::LightMagenta,
+        (6, true) => Color::LightCyan,
+        _ => Color::White,
+    }
+}
@@ -0,0 +1,190 @@
+use tui::style::{Color, Modifier, Style};
+
+pub mod ls_colors;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub text: Color,
+    pub list_border: Color,
+    pub preview_border: Color,
+    pub info_border: Color,
+    pub directory: Color,
+    pub link: Color,
+    pub executable: Color,
+    pub socket: Color,
+    pub fifo: Color,
+    pub device: Color,
+    pub orphan: Color,
+    pub highlight_fg: Color,
+    pub highlight_bg: Color,
+    pub error: Color,
+}
+
+pub const THEME_NAMES: [&str; 5] = ["default", "gruvbox", "nord", "solarized", "mono"];
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            text: Color::White,
+            list_border: Color::Yellow,
+            preview_border: Color::LightBlue,
+            info_border: Color::Green,
+            directory: Color::Blue,
+            link: Color::Cyan,
+            executable: Color::Green,
+            socket: Color::Magenta,
+            fifo: Color::Yellow,
+            device: Color::LightYellow,
+            orphan: Color::Red,
+            highlight_fg: Color::Black,
+            highlight_bg: Color::Yellow,
+            error: Color::Red,
+        }
+    }
+}
+
+impl Theme {
+    pub fn named(name: &str) -> Option<Theme> {
+        let theme = match name {
+            "default" => Theme::default(),
+            "gruvbox" => Theme {
+                text: Color::Rgb(235, 219, 178),
+                list_border: Color::Rgb(250, 189, 47),
+                preview_border: Color::Rgb(131, 165, 152),
+                info_border: Color::Rgb(184, 187, 38),
+                directory: Color::Rgb(131, 165, 152),
+                link: Color::Rgb(142, 192, 124),
+                executable: Color::Rgb(184, 187, 38),
+                socket: Color::Rgb(211, 134, 155),
+                fifo: Color::Rgb(250, 189, 47),
+                device: Color::Rgb(254, 128, 25),
+                orphan: Color::Rgb(251, 73, 52),
+                highlight_fg: Color::Rgb(40, 40, 40),
+                highlight_bg: Color::Rgb(250, 189, 47),
+                error: Color::Rgb(251, 73, 52),
+            },
+            "nord" => Theme {
+                text: Color::Rgb(216, 222, 233),
+                list_border: Color::Rgb(136, 192, 208),
+                preview_border: Color::Rgb(129, 161, 193),
+                info_border: Color::Rgb(163, 190, 140),
+                directory: Color::Rgb(129, 161, 193),
+                link: Color::Rgb(143, 188, 187),
+                executable: Color::Rgb(163, 190, 140),
+                socket: Color::Rgb(180, 142, 173),
+                fifo: Color::Rgb(235, 203, 139),
+                device: Color::Rgb(208, 135, 112),
+                orphan: Color::Rgb(191, 97, 106),
+                highlight_fg: Color::Rgb(46, 52, 64),
+                highlight_bg: Color::Rgb(136, 192, 208),
+                error: Color::Rgb(191, 97, 106),
+            },
+            "solarized" => Theme {
+                text: Color::Rgb(147, 161, 161),
+                list_border: Color::Rgb(181, 137, 0),
+                preview_border: Color::Rgb(38, 139, 210),
+                info_border: Color::Rgb(133, 153, 0),
+                directory: Color::Rgb(38, 139, 210),
+                link: Color::Rgb(42, 161, 152),
+                executable: Color::Rgb(133, 153, 0),
+                socket: Color::Rgb(211, 54, 130),
+                fifo: Color::Rgb(181, 137, 0),
+                device: Color::Rgb(203, 75, 22),
+                orphan: Color::Rgb(220, 50, 47),
+                highlight_fg: Color::Rgb(0, 43, 54),
+                highlight_bg: Color::Rgb(181, 137, 0),
+                error: Color::Rgb(220, 50, 47),
+            },
+            "mono" => Theme {
+                text: Color::Reset,
+                list_border: Color::Reset,
+                preview_border: Color::Reset,
+                info_border: Color::Reset,
+                directory: Color::Reset,
+                link: Color::Reset,
+                executable: Color::Reset,
+                socket: Color::Reset,
+                fifo: Color::Reset,
+                device: Color::Reset,
+                orphan: Color::Reset,
+                highlight_fg: Color::Reset,
+                highlight_bg: Color::Reset,
+                error: Color::Reset,
+            },
+            _ => return None,
+        };
+
+        Some(theme)
+    }
+
+    pub fn highlight_style(&self) -> Style {
+        let style = Style::default().add_modifier(Modifier::BOLD);
+
+        if self.highlight_bg == Color::Reset {
+            return style.add_modifier(Modifier::REVERSED);
+        }
+
+        style
+            .bg(self.highlight_bg)
+            .fg(self.highlight_fg)
+    }
+
+    // fallback file colors for the ls(1) kinds LS_COLORS doesn't set
+    pub fn kind_style(&self, kind: &str) -> Style {
+        let style = Style::default();
+
+        match kind {
+            "di" => style.fg(self.directory),
+            "ln" => style.fg(self.link),
+            "or" | "mi" => style.fg(self.orphan),
+            "ex" => style.fg(self.executable).add_modifier(Modifier::BOLD),
+            "so" => style.fg(self.socket),
+            "pi" => style.fg(self.fifo),
+            "bd" | "cd" => style.fg(self.device).add_modifier(Modifier::BOLD),
+            _ => style,
+        }
+    }
+}
+
+pub fn parse_color(color: &str) -> Result<Color, String> {
+    let name = color.trim().to_ascii_lowercase().replace(&['-', '_', ' '][..], "");
+
+    let parsed = match name.as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => {
+            if let Some(hex) = name.strip_prefix('#') {
+                // checked before slicing, a multibyte character would split
+                if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(format!("invalid hex color `{}`, expected #rrggbb", color));
+                }
+                let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16);
+                match (channel(0), channel(2), channel(4)) {
+                    (Ok(r), Ok(g), Ok(b)) => Color::Rgb(r, g, b),
+                    _ => return Err(format!("invalid hex color `{}`", color)),
+                }
+            } else if let Ok(idx) = name.parse::<u8>() {
+                Color::Indexed(idx)
+            } else {
+                return Err(format!("unknown color `{}`", color));
+            }
+        }
+    };
+
+    Ok(parsed)
+}
@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::entry::file_data::FileData;
+use crate::entry::listing::{self, ListingOptions};
+
+pub struct TreeNode {
+    pub file: FileData,
+    pub guides: String,
+    pub expanded: bool,
+}
+
+// only expanded directories are ever read
+#[derive(Clone, Debug, Default)]
+pub struct Tree {
+    expanded: HashSet<PathBuf>,
+}
+
+impl Tree {
+    pub fn is_expanded(&self, path: &Path) -> bool {
+        self.expanded.contains(path)
+    }
+
+    pub fn expand(&mut self, path: &Path) {
+        self.expanded.insert(path.to_path_buf());
+    }
+
+    pub fn collapse(&mut self, path: &Path) {
+        self.expanded.remove(path);
+    }
+
+    pub fn nodes(&self, root: &Path, opts: &ListingOptions) -> Vec<TreeNode> {
+        let mut nodes = Vec::new();
+        self.walk(root, opts, 0, "", &mut nodes);
+
+        nodes
+    }
+
+    fn walk(&self, dir: &Path, opts: &ListingOptions, depth: usize, prefix: &str,
+            nodes: &mut Vec<TreeNode>) {
+        let files = listing::read_dir(dir, opts).unwrap_or_default();
+        let count = files.len();
+
+        for (idx, file) in files.into_iter().enumerate() {
+            let last = idx + 1 == count;
+            let (guides, child_prefix) = match depth {
+                0 => (String::new(), String::new()),
+                _ if last => (format!("{}└─ ", prefix), format!("{}   ", prefix)),
+                _ => (format!("{}├─ ", prefix), format!("{}│  ", prefix)),
+            };
+
+            let path = file.path().to_path_buf();
+            let expanded = file.is_dir() && self.is_expanded(&path);
+            nodes.push(TreeNode { file, guides, expanded });
+
+            if expanded {
+                self.walk(&path, opts, depth + 1, &child_prefix, nodes);
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+use std::{env, fs, io};
+
+use crate::archive::{self, Entry, Format};
+use crate::entry::file_data::FileData;
+
+// a directory inside an archive is the archive's path followed by the path
+// within it, `/tmp/src.zip/src/bin`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+    pub archive: PathBuf,
+    pub format: Format,
+    pub inner: PathBuf,
+}
+
+// archive listings by path, read again once the archive changes
+type ListingCache = HashMap<PathBuf, (SystemTime, Arc<Vec<Entry>>)>;
+
+fn listings() -> &'static Mutex<ListingCache> {
+    static LISTINGS: OnceLock<Mutex<ListingCache>> = OnceLock::new();
+    LISTINGS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// set while the working directory is inside an archive
+static CURRENT_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+// where in an archive `path` is, `None` for paths on disk; an archive's
+// own path is its root
+pub fn locate(path: &Path) -> Option<Location> {
+    for ancestor in path.ancestors() {
+        match fs::metadata(ancestor) {
+            Ok(metadata) if metadata.is_file() => {
+                return Some(Location {
+                    archive: ancestor.to_path_buf(),
+                    format: Format::detect(ancestor)?,
+                    inner: path.strip_prefix(ancestor).ok()?.to_path_buf(),
+                });
+            }
+            Ok(_) => return None,
+            Err(_) => {}
+        }
+    }
+
+    None
+}
+
+// a directory in an archive, the archive itself included
+pub fn in_archive(path: &Path) -> bool {
+    locate(path).is_some()
+}
+
+// something stored in an archive, not the archive
+pub fn is_member(path: &Path) -> bool {
+    locate(path).is_some_and(|location| !location.inner.as_os_str().is_empty())
+}
+
+// the entries of a directory on disk or in an archive, unsorted
+pub fn read_dir(path: &Path) -> io::Result<Vec<FileData>> {
+    let location = match locate(path) {
+        Some(location) => location,
+        None => return read_local(path),
+    };
+
+    let modified = fs::metadata(&location.archive)?.modified()?;
+    let entries = listing(&location, modified)?;
+    children(path, &location.inner, &entries, modified)
+}
+
+fn read_local(path: &Path) -> io::Result<Vec<FileData>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+
+        if let Ok(entry_data) = FileData::new(entry) {
+            files.push(entry_data);
+        }
+    }
+
+    Ok(files)
+}
+
+fn listing(location: &Location, modified: SystemTime) -> io::Result<Arc<Vec<Entry>>> {
+    if let Some((cached, entries)) = listings().lock().unwrap().get(&location.archive) {
+        if *cached == modified {
+            return Ok(entries.clone());
+        }
+    }
+
+    let entries = Arc::new(archive::entries(&location.archive, location.format)?);
+    listings()
+        .lock()
+        .unwrap()
+        .insert(location.archive.clone(), (modified, entries.clone()));
+
+    Ok(entries)
+}
+
+// the members directly below `inner`; directories only implied by deeper
+// members get the archive's mtime
+fn children(dir: &Path, inner: &Path, entries: &[Entry],
+            modified: SystemTime) -> io::Result<Vec<FileData>> {
+    let mut children: BTreeMap<String, Entry> = BTreeMap::new();
+    let mut found = inner.as_os_str().is_empty();
+
+    for entry in entries {
+        let member = match entry.relative_path() {
+            Some(member) if !member.as_os_str().is_empty() => member,
+            _ => continue,
+        };
+        if member == inner {
+            found |= entry.is_dir();
+            continue;
+        }
+        let rest = match member.strip_prefix(inner) {
+            Ok(rest) => rest,
+            Err(_) => continue,
+        };
+        found = true;
+
+        let mut components = rest.iter();
+        let name = components.next().unwrap_or_default().to_string_lossy().into_owned();
+        if components.next().is_some() {
+            let path = inner.join(&name).to_string_lossy().into_owned();
+            children.entry(name).or_insert_with(|| Entry::directory(path, modified));
+        } else {
+            children.insert(name, entry.clone());
+        }
+    }
+
+    if !found {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "no such directory in the archive"));
+    }
+
+    Ok(children
+        .into_iter()
+        .map(|(name, entry)| FileData::from_entry(dir.join(name), &entry))
+        .collect())
+}
+
+// what everything below `path` in an archive adds up to uncompressed
+pub fn total_size(path: &Path) -> Option<u64> {
+    let location = locate(path)?;
+    let modified = fs::metadata(&location.archive).and_then(|metadata| metadata.modified()).ok()?;
+    let entries = listing(&location, modified).ok()?;
+
+    Some(entries
+        .iter()
+        .filter(|entry| entry.relative_path().is_some_and(|member| member.starts_with(&location.inner)))
+        .map(|entry| entry.size)
+        .sum())
+}
+
+// like `env::set_current_dir`, except that a directory in an archive can
+// be the working directory too; the process itself stays where it was
+pub fn set_current_dir(path: &Path) -> io::Result<()> {
+    let mut current = CURRENT_DIR.lock().unwrap();
+    if in_archive(path) {
+        read_dir(path)?;
+        *current = Some(path.to_path_buf());
+    } else {
+        env::set_current_dir(path)?;
+        *current = None;
+    }
+
+    Ok(())
+}
+
+pub fn current_dir() -> io::Result<PathBuf> {
+    match CURRENT_DIR.lock().unwrap().clone() {
+        Some(dir) => Ok(dir),
+        None => env::current_dir(),
+    }
+}
+
+// extracts the archive member `src`, with whatever is below it, into `dir`
+pub fn copy_out(src: &Path, dir: &Path) -> io::Result<()> {
+    let location = locate(src)
+        .filter(|location| !location.inner.as_os_str().is_empty())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not in an archive"))?;
+
+    if let Some(name) = src.file_name() {
+        if dir.join(name).exists() {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "destination exists"));
+        }
+    }
+
+    archive::extract(&location.archive, location.format, &location.inner, dir, |_| {})?;
+    Ok(())
+}
+
+pub fn read_only() -> io::Error {
+    io::Error::new(io::ErrorKind::PermissionDenied, "archives are read-only")
+}
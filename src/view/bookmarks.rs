@@ -0,0 +1,85 @@
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::Style;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph};
+use tui::Frame;
+
+use super::centered_rect;
+use crate::bookmarks::Bookmarks;
+use crate::theme::Theme;
+
+pub struct BookmarkPopup {
+    pub state: ListState,
+    pub rename: Option<String>,
+}
+
+impl Default for BookmarkPopup {
+    fn default() -> BookmarkPopup {
+        let mut state = ListState::default();
+        state.select(Some(0));
+
+        BookmarkPopup { state, rename: None }
+    }
+}
+
+impl BookmarkPopup {
+    pub fn selected<'a>(&self, bookmarks: &'a Bookmarks) -> Option<&'a str> {
+        let idx = self.state.selected()?;
+        bookmarks.entries().nth(idx).map(|(name, _)| name)
+    }
+
+    pub fn move_selection(&mut self, bookmarks: &Bookmarks, offset: isize) {
+        let count = bookmarks.entries().count();
+        if count == 0 {
+            return;
+        }
+
+        let selected = self.state.selected().unwrap_or(0) as isize;
+        let idx = (selected + offset).rem_euclid(count as isize);
+        self.state.select(Some(idx as usize));
+    }
+}
+
+pub fn draw_bookmarks<B: Backend>(f: &mut Frame<B>, area: Rect, popup: &mut BookmarkPopup,
+                                  bookmarks: &Bookmarks, theme: &Theme) {
+    let area = centered_rect(60, 60, area);
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)].as_ref())
+        .margin(1)
+        .split(area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().fg(theme.text))
+        .title(" Bookmarks ")
+        .border_style(Style::default().fg(theme.list_border))
+        .border_type(BorderType::Thick);
+    f.render_widget(block, area);
+
+    let width = bookmarks.entries().map(|(name, _)| name.chars().count()).max().unwrap_or(0);
+    let items: Vec<_> = bookmarks
+        .entries()
+        .map(|(name, path)| {
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{:width$}  ", name, width = width),
+                             Style::default().fg(theme.directory)),
+                Span::raw(path.to_string_lossy().into_owned()),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(theme.highlight_style())
+        .highlight_symbol(">");
+    f.render_stateful_widget(list, chunks[0], &mut popup.state);
+
+    let footer = match &popup.rename {
+        Some(name) => format!("rename to: {}", name),
+        None => String::from("<CR> jump  r rename  d delete  <Esc> close"),
+    };
+    f.render_widget(Paragraph::new(footer), chunks[1]);
+}
@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local};
+use tui::backend::Backend;
+use tui::layout::{Constraint, Rect};
+use tui::style::{Modifier, Style};
+use tui::widgets::{Block, BorderType, Borders, Cell, Row, Table, TableState};
+use tui::Frame;
+use users::{Groups, Users, UsersCache};
+
+use super::name_span;
+use crate::config::Config;
+use crate::entry::file_data::FileData;
+use crate::entry::listing::SortKey;
+use crate::pane::Pane;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Column {
+    Mode,
+    Owner,
+    Group,
+    Size,
+    Modified,
+    Inode,
+    Links,
+    Mime,
+    Name,
+}
+
+pub const DEFAULT_COLUMNS: [Column; 6] = [
+    Column::Mode,
+    Column::Owner,
+    Column::Group,
+    Column::Size,
+    Column::Modified,
+    Column::Name,
+];
+
+// the first columns to go when the terminal gets too narrow
+const DROP_ORDER: [Column; 8] = [
+    Column::Mime,
+    Column::Inode,
+    Column::Links,
+    Column::Group,
+    Column::Owner,
+    Column::Modified,
+    Column::Mode,
+    Column::Size,
+];
+
+const NAME_MIN_WIDTH: u16 = 12;
+const COLUMN_SPACING: u16 = 1;
+
+// MIME types remembered before the cache starts over
+const MIME_CACHE_SIZE: usize = 4096;
+
+// what the table keeps between frames: where the cursor is, how far it is
+// scrolled and the lookups too slow to repeat for every row every time
+#[derive(Clone, Default)]
+pub struct TableView {
+    selected: Option<usize>,
+    offset: usize,
+    users: Rc<UsersCache>,
+    mimes: HashMap<PathBuf, (SystemTime, String)>,
+}
+
+impl TableView {
+    pub fn select(&mut self, selected: Option<usize>) {
+        self.selected = selected;
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    // the rows shown out of `len`; like tui's own tables the window only
+    // moves once the cursor leaves it
+    pub fn window(&mut self, cursor: Option<usize>, height: usize, len: usize) -> Range<usize> {
+        let height = height.max(1);
+        if let Some(cursor) = cursor {
+            if cursor < self.offset {
+                self.offset = cursor;
+            } else if cursor >= self.offset + height {
+                self.offset = cursor + 1 - height;
+            }
+        }
+        self.offset = self.offset.min(len.saturating_sub(height));
+
+        self.offset..len.min(self.offset + height)
+    }
+
+    pub fn text(&mut self, column: Column, file: &FileData) -> String {
+        if column != Column::Mime {
+            return column.text(file, &self.users);
+        }
+
+        let modified = file.modified();
+        if let Some((cached, mime)) = self.mimes.get(file.path()) {
+            if *cached == modified {
+                return mime.clone();
+            }
+        }
+
+        if self.mimes.len() >= MIME_CACHE_SIZE {
+            self.mimes.clear();
+        }
+        let mime = file.get_mime_type().unwrap_or_default();
+        self.mimes.insert(file.path().to_path_buf(), (modified, mime.clone()));
+        mime
+    }
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Column, String> {
+        match name {
+            "mode" => Ok(Column::Mode),
+            "owner" | "user" => Ok(Column::Owner),
+            "group" => Ok(Column::Group),
+            "size" => Ok(Column::Size),
+            "modified" | "mtime" => Ok(Column::Modified),
+            "inode" => Ok(Column::Inode),
+            "links" | "nlink" => Ok(Column::Links),
+            "mime" => Ok(Column::Mime),
+            "name" => Ok(Column::Name),
+            _ => Err(format!("unknown column `{}`, expected one of mode, owner, \
+                              group, size, modified, inode, links, mime, name", name)),
+        }
+    }
+}
+
+impl Column {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Column::Mode => "Mode",
+            Column::Owner => "Owner",
+            Column::Group => "Group",
+            Column::Size => "Size",
+            Column::Modified => "Modified",
+            Column::Inode => "Inode",
+            Column::Links => "Links",
+            Column::Mime => "Type",
+            Column::Name => "Name",
+        }
+    }
+
+    fn max_width(&self) -> u16 {
+        match self {
+            Column::Owner | Column::Group => 12,
+            Column::Mime => 24,
+            _ => u16::MAX,
+        }
+    }
+
+    fn right_aligned(&self) -> bool {
+        matches!(self, Column::Size | Column::Inode | Column::Links)
+    }
+
+    fn sorted_by(&self, sort: SortKey) -> bool {
+        match self {
+            Column::Name => sort == SortKey::Name || sort == SortKey::Extension,
+            Column::Size => sort == SortKey::Size,
+            Column::Modified => sort == SortKey::Modified,
+            _ => false,
+        }
+    }
+
+    pub fn text(&self, file: &FileData, users: &UsersCache) -> String {
+        match self {
+            Column::Mode => file.mode_string(),
+            Column::Owner => users
+                .get_user_by_uid(file.uid())
+                .map(|user| user.name().to_string_lossy().into_owned())
+                .unwrap_or_else(|| file.uid().to_string()),
+            Column::Group => users
+                .get_group_by_gid(file.gid())
+                .map(|group| group.name().to_string_lossy().into_owned())
+                .unwrap_or_else(|| file.gid().to_string()),
+            Column::Size => file.size_string(),
+            Column::Modified => {
+                let mod_time: DateTime<Local> = file.modified().into();
+                mod_time.format("%b %e %H:%M").to_string()
+            }
+            Column::Inode => file.inode().to_string(),
+            Column::Links => file.links().to_string(),
+            Column::Mime => file.get_mime_type().unwrap_or_default(),
+            Column::Name => {
+                let mut name = file.name.clone();
+                if file.is_dir() {
+                    name.push('/');
+                }
+                name
+            }
+        }
+    }
+}
+
+// indices of the columns that fit into `available`, dropping the least
+// important ones first; the name column always stays
+pub fn fit_columns(columns: &[Column], widths: &[u16], available: u16) -> Vec<usize> {
+    let mut kept: Vec<usize> = (0..columns.len()).collect();
+
+    let needed = |kept: &Vec<usize>| -> u32 {
+        kept.iter()
+            .map(|&idx| match columns[idx] {
+                Column::Name => NAME_MIN_WIDTH as u32,
+                _ => widths[idx] as u32,
+            })
+            .sum::<u32>() + (kept.len().saturating_sub(1) as u32) * COLUMN_SPACING as u32
+    };
+
+    for drop in DROP_ORDER.iter() {
+        if needed(&kept) <= available as u32 {
+            break;
+        }
+        kept.retain(|&idx| columns[idx] != *drop);
+    }
+
+    kept
+}
+
+// only the rows in view are looked at, so a huge directory costs no more
+// than a small one; without `cursor` no row is highlighted
+pub fn render_table<B: Backend>(f: &mut Frame<B>, area: Rect, pane: &mut Pane, files: &[FileData],
+                                title: String, cursor: bool, config: &Config) {
+    let theme = &config.theme;
+    let columns = &config.layout.columns;
+    let listing = &pane.listing;
+    let selection = &pane.selection;
+    let view = &mut pane.table;
+
+    // borders and the header
+    let height = area.height.saturating_sub(3) as usize;
+    let cursor = view.selected().filter(|_| cursor);
+    let window = view.window(cursor, height, files.len());
+    let files = &files[window.clone()];
+
+    let texts: Vec<Vec<String>> = files
+        .iter()
+        .map(|file| columns.iter().map(|&column| view.text(column, file)).collect())
+        .collect();
+
+    let widths: Vec<u16> = columns
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| {
+            texts.iter()
+                .map(|row| row[idx].chars().count() as u16)
+                .chain(std::iter::once(column.title().len() as u16 + 1))
+                .max()
+                .unwrap_or(0)
+                .min(column.max_width())
+        })
+        .collect();
+
+    // borders and the highlight symbol
+    let available = area.width.saturating_sub(3);
+    let kept = fit_columns(columns, &widths, available);
+
+    let header = Row::new(kept.iter().map(|&idx| {
+        let column = columns[idx];
+        let mut title = column.title().to_string();
+        if column.sorted_by(listing.sort) {
+            title.push(if listing.reverse { '▼' } else { '▲' });
+        }
+        Cell::from(title)
+    }))
+    .style(Style::default().fg(theme.list_border).add_modifier(Modifier::BOLD));
+
+    let rows = files.iter().zip(texts.iter()).map(|(file, row)| {
+        Row::new(kept.iter().map(|&idx| {
+            let column = columns[idx];
+            let text = if column.right_aligned() {
+                format!("{:>width$}", row[idx], width = widths[idx] as usize)
+            } else {
+                row[idx].clone()
+            };
+
+            match column {
+                Column::Name => Cell::from(name_span(text, config.ls_colors.style_for(file, theme),
+                                                     selection.contains(file.path()))),
+                _ => Cell::from(text),
+            }
+        }))
+    });
+
+    let constraints: Vec<Constraint> = kept
+        .iter()
+        .map(|&idx| match columns[idx] {
+            Column::Name => Constraint::Min(NAME_MIN_WIDTH),
+            _ => Constraint::Length(widths[idx]),
+        })
+        .collect();
+
+    let table = Table::new(rows)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(theme.text))
+                .title(title)
+                .border_style(Style::default().fg(theme.list_border))
+                .border_type(BorderType::Thick),
+        )
+        .widths(&constraints)
+        .column_spacing(COLUMN_SPACING)
+        .highlight_style(theme.highlight_style())
+        .highlight_symbol(">");
+
+    let mut state = TableState::default();
+    state.select(cursor.map(|cursor| cursor - window.start));
+    f.render_stateful_widget(table, area, &mut state);
+}
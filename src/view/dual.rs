@@ -0,0 +1,35 @@
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::widgets::ListState;
+use tui::Frame;
+
+use super::{columns, draw_listing, render_file_list};
+use crate::config::Config;
+use crate::entry::file_data::FileData;
+use crate::pane::{DualPane, Pane};
+use crate::tree::TreeNode;
+
+pub fn draw_dual<B: Backend>(f: &mut Frame<B>, area: Rect, panes: &mut DualPane,
+                             files: &[FileData], nodes: &[TreeNode], config: &Config) -> usize {
+    let halves = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(area);
+
+    let active = panes.active;
+    draw_inactive(f, halves[1 - active], panes.other_mut(), config);
+    draw_listing(f, halves[active], panes.active_mut(), files, nodes, config)
+}
+
+// like the active panel, but without a cursor
+fn draw_inactive<B: Backend>(f: &mut Frame<B>, area: Rect, pane: &mut Pane, config: &Config) {
+    let files = pane.files();
+    let title = format!(" {} ", pane.path.to_string_lossy());
+
+    if config.layout.long_listing {
+        columns::render_table(f, area, pane, &files, title, false, config);
+    } else {
+        let list = render_file_list(title, &files, &pane.selection, config);
+        f.render_stateful_widget(list, area, &mut ListState::default());
+    }
+}
@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::Style;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph};
+use tui::Frame;
+
+use super::centered_rect;
+use crate::frecency::{self, Frecency};
+use crate::theme::Theme;
+
+pub struct FrecencyPicker {
+    pub query: String,
+    pub state: ListState,
+}
+
+impl Default for FrecencyPicker {
+    fn default() -> FrecencyPicker {
+        let mut state = ListState::default();
+        state.select(Some(0));
+
+        FrecencyPicker { query: String::new(), state }
+    }
+}
+
+impl FrecencyPicker {
+    pub fn matches(&self, frecency: &Frecency) -> Vec<(PathBuf, f64)> {
+        let fragments: Vec<&str> = self.query.split_whitespace().collect();
+
+        frecency.query(&fragments, frecency::now())
+            .into_iter()
+            .filter(|(path, _)| path.is_dir())
+            .collect()
+    }
+
+    pub fn selected(&self, frecency: &Frecency) -> Option<PathBuf> {
+        let idx = self.state.selected()?;
+        self.matches(frecency).into_iter().nth(idx).map(|(path, _)| path)
+    }
+
+    pub fn move_selection(&mut self, frecency: &Frecency, offset: isize) {
+        let count = self.matches(frecency).len();
+        if count == 0 {
+            return;
+        }
+
+        let selected = self.state.selected().unwrap_or(0) as isize;
+        let idx = (selected + offset).rem_euclid(count as isize);
+        self.state.select(Some(idx as usize));
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.state.select(Some(0));
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.state.select(Some(0));
+    }
+}
+
+pub fn draw_picker<B: Backend>(f: &mut Frame<B>, area: Rect, picker: &mut FrecencyPicker,
+                               frecency: &Frecency, theme: &Theme) {
+    let area = centered_rect(70, 60, area);
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(3)].as_ref())
+        .margin(1)
+        .split(area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().fg(theme.text))
+        .title(" Jump ")
+        .border_style(Style::default().fg(theme.list_border))
+        .border_type(BorderType::Thick);
+    f.render_widget(block, area);
+
+    f.render_widget(Paragraph::new(format!("> {}", picker.query)), chunks[0]);
+    f.set_cursor(chunks[0].x + 2 + picker.query.chars().count() as u16, chunks[0].y);
+
+    let items: Vec<_> = picker
+        .matches(frecency)
+        .into_iter()
+        .map(|(path, score)| {
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{:>7.1}  ", score), Style::default().fg(theme.info_border)),
+                Span::styled(path.to_string_lossy().into_owned(),
+                             Style::default().fg(theme.directory)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(theme.highlight_style())
+        .highlight_symbol(">");
+    f.render_stateful_widget(list, chunks[1], &mut picker.state);
+}
@@ -0,0 +1,81 @@
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::Style;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph};
+use tui::Frame;
+
+use super::centered_rect;
+use crate::jobs::{JobStatus, Jobs};
+use crate::theme::Theme;
+
+pub struct JobsPopup {
+    pub state: ListState,
+}
+
+impl Default for JobsPopup {
+    fn default() -> JobsPopup {
+        let mut state = ListState::default();
+        state.select(Some(0));
+
+        JobsPopup { state }
+    }
+}
+
+impl JobsPopup {
+    pub fn move_selection(&mut self, jobs: &Jobs, offset: isize) {
+        if jobs.is_empty() {
+            return;
+        }
+
+        let selected = self.state.selected().unwrap_or(0) as isize;
+        let idx = (selected + offset).rem_euclid(jobs.len() as isize);
+        self.state.select(Some(idx as usize));
+    }
+}
+
+pub fn draw_jobs<B: Backend>(f: &mut Frame<B>, area: Rect, popup: &mut JobsPopup, jobs: &Jobs,
+                             theme: &Theme) {
+    let area = centered_rect(70, 60, area);
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)].as_ref())
+        .margin(1)
+        .split(area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().fg(theme.text))
+        .title(" Jobs ")
+        .border_style(Style::default().fg(theme.list_border))
+        .border_type(BorderType::Thick);
+    f.render_widget(block, area);
+
+    let items: Vec<_> = jobs
+        .iter()
+        .map(|job| {
+            let (status, color) = match job.status() {
+                JobStatus::Running => match job.progress() {
+                    Some((done, total)) => (format!("{}/{}", done, total), theme.info_border),
+                    None => (String::from("running"), theme.info_border),
+                },
+                JobStatus::Finished { success: true, summary } => (summary, theme.text),
+                JobStatus::Finished { success: false, summary } => (summary, theme.error),
+            };
+            ListItem::new(Spans::from(vec![
+                Span::raw(format!("[{}] ", job.id)),
+                Span::styled(format!("{:<12} ", status), Style::default().fg(color)),
+                Span::raw(job.title.clone()),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(theme.highlight_style())
+        .highlight_symbol(">");
+    f.render_stateful_widget(list, chunks[0], &mut popup.state);
+
+    f.render_widget(Paragraph::new("<CR> output  <Esc> close"), chunks[1]);
+}
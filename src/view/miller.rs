@@ -0,0 +1,75 @@
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::widgets::ListState;
+use tui::Frame;
+
+use super::{draw_dir_preview, draw_listing, render_file_list, render_info, render_preview};
+use crate::config::Config;
+use crate::entry::file_data::FileData;
+use crate::entry::listing::read_dir;
+use crate::pane::Pane;
+use crate::tree::TreeNode;
+
+pub fn draw_miller<B: Backend>(f: &mut Frame<B>, area: Rect, pane: &mut Pane,
+                               files: &[FileData], nodes: &[TreeNode], config: &Config) -> usize {
+    let ratios = &config.layout.miller_ratios;
+    let total: u32 = ratios.iter().map(|&ratio| ratio as u32).sum();
+    let constraints: Vec<Constraint> = ratios
+        .iter()
+        .map(|&ratio| Constraint::Ratio(ratio as u32, total))
+        .collect();
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
+        .split(area);
+
+    draw_parent(f, columns[0], pane, config);
+    let list_height = draw_listing(f, columns[1], pane, files, nodes, config);
+
+    let selected_file = pane.marked_file.selected().and_then(|idx| files.get(idx));
+    draw_preview(f, columns[2], pane, selected_file, config);
+
+    list_height
+}
+
+fn draw_parent<B: Backend>(f: &mut Frame<B>, area: Rect, pane: &Pane, config: &Config) {
+    let (parent, current) = match (pane.path.parent(), pane.path.file_name()) {
+        (Some(parent), Some(current)) => (parent, current.to_string_lossy()),
+        _ => {
+            let list = render_file_list(String::from(" / "), &[], &pane.selection, config);
+            f.render_widget(list, area);
+            return;
+        }
+    };
+
+    let files = read_dir(parent, &pane.listing).unwrap_or_default();
+    let mut state = ListState::default();
+    state.select(files.iter().position(|file| file.name == current));
+
+    let title = format!(" {} ", parent.to_string_lossy());
+    let list = render_file_list(title, &files, &pane.selection, config);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_preview<B: Backend>(f: &mut Frame<B>, area: Rect, pane: &mut Pane,
+                            selected_file: Option<&FileData>, config: &Config) {
+    if let Some(dir) = selected_file.filter(|file| file.is_dir()) {
+        draw_dir_preview(f, area, pane, dir, config);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Min(3),
+                Constraint::Length(config.layout.info_height),
+            ].as_ref()
+        )
+        .split(area);
+
+    let preview = render_preview(selected_file, &mut pane.preview, chunks[0], &config.theme);
+    f.render_widget(preview, chunks[0]);
+    f.render_widget(render_info(selected_file, &pane.preview, &config.theme), chunks[1]);
+}
@@ -0,0 +1,261 @@
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, BorderType, Borders, List, ListItem, ListState, Paragraph, Tabs};
+use tui::Frame;
+
+use crate::config::Config;
+use crate::entry::file_data::FileData;
+use crate::entry::listing::read_dir;
+use crate::pane::{Pane, PreviewState};
+use crate::preview::{self, dir::Summary};
+use crate::theme::Theme;
+use crate::tree::TreeNode;
+
+pub mod bookmarks;
+pub mod columns;
+pub mod dual;
+pub mod frecency;
+pub mod jobs;
+pub mod miller;
+pub mod opener;
+pub mod output;
+pub mod tree;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ViewMode {
+    Split,
+    Miller,
+    Dual,
+}
+
+impl FromStr for ViewMode {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<ViewMode, String> {
+        match name {
+            "split" => Ok(ViewMode::Split),
+            "miller" => Ok(ViewMode::Miller),
+            "dual" => Ok(ViewMode::Dual),
+            _ => Err(format!("unknown view `{}`, expected split, miller or dual", name)),
+        }
+    }
+}
+
+pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_y) / 2),
+                Constraint::Percentage(percent_y),
+                Constraint::Percentage((100 - percent_y) / 2),
+            ].as_ref()
+        )
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_x) / 2),
+                Constraint::Percentage(percent_x),
+                Constraint::Percentage((100 - percent_x) / 2),
+            ].as_ref()
+        )
+        .split(rows[1])[1]
+}
+
+// marked entries get a `+` in front of the name
+pub fn name_span<'a>(mut name: String, style: Style, marked: bool) -> Span<'a> {
+    if marked {
+        name.insert(0, '+');
+        return Span::styled(name, style.add_modifier(Modifier::BOLD));
+    }
+
+    Span::styled(name, style)
+}
+
+pub fn render_file_list<'a>(title: String, files: &[FileData], selection: &BTreeSet<PathBuf>,
+                            config: &Config) -> List<'a> {
+    let theme = &config.theme;
+
+    let files_block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().fg(theme.text))
+        .title(title)
+        .border_style(Style::default().fg(theme.list_border))
+        .border_type(BorderType::Thick);
+
+    let items: Vec<_> = files
+        .iter()
+        .map(|file| {
+            let mut file_name = file.name.clone();
+            let file_color = config.ls_colors.style_for(file, theme);
+            if file.is_dir() {
+                file_name.push('/');
+            }
+            let marked = selection.contains(file.path());
+            ListItem::new(Spans::from(vec![name_span(file_name, file_color, marked)]))
+        })
+        .collect();
+
+    List::new(items)
+        .block(files_block)
+        .highlight_style(theme.highlight_style())
+        .highlight_symbol(">")
+}
+
+pub fn render_preview<'a>(selected_file: Option<&FileData>, state: &mut PreviewState,
+                          area: Rect, theme: &Theme) -> Paragraph<'a> {
+    // inside the borders
+    let columns = area.width.saturating_sub(2) as usize;
+    let lines = area.height.saturating_sub(2) as usize;
+    let preview = match selected_file {
+        Some(file) => preview::file_preview(file, state, columns, lines),
+        None => {
+            state.follow(None);
+            Vec::new()
+        }
+    };
+    let title = match &state.header {
+        _ if state.hex => String::from(" Preview [hex] "),
+        Some(header) => format!(" Preview  {} ", header),
+        None => String::from(" Preview "),
+    };
+
+    Paragraph::new(preview)
+        .style(Style::default().fg(theme.text))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.preview_border))
+                .title(title)
+                .border_type(BorderType::Thick),
+        )
+}
+
+// the children of `dir` as the listing would show them, with the cursor
+// where it was last left in there
+pub fn draw_dir_preview<B: Backend>(f: &mut Frame<B>, area: Rect, pane: &mut Pane,
+                                    dir: &FileData, config: &Config) {
+    let path = dir.path();
+    pane.preview.follow(Some(path));
+    let files = read_dir(path, &pane.listing).unwrap_or_default();
+
+    let mut state = ListState::default();
+    if let Some(name) = pane.cursor_memory.recall(path) {
+        state.select(files.iter().position(|file| file.name == name));
+    }
+
+    let title = format!(" {}/  {} ", dir.name, Summary::new(path, &files));
+    let list = render_file_list(title, &files, &pane.selection, config);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn render_info<'a>(selected_file: Option<&FileData>, preview: &PreviewState,
+                       theme: &Theme) -> Paragraph<'a> {
+    let mut info = String::from("");
+    if let Some(file) = selected_file {
+        // what the preview already found out about the file
+        let previewed = preview.path.as_deref() == Some(file.path());
+        info = match preview.mime.as_deref().filter(|_| previewed) {
+            Some(mime) => file.info_with_mime(mime),
+            None => file.info(),
+        };
+        // after the MIME type, which is the last line
+        if let Some(image) = preview.image_info.as_ref().filter(|_| previewed) {
+            info.push_str(&format!("  {}", image));
+        }
+    }
+
+    Paragraph::new(info)
+        .style(Style::default().fg(theme.text))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.info_border))
+                .title(" Info ")
+                .border_type(BorderType::Thick),
+        )
+}
+
+pub fn render_tab_bar<'a>(titles: Vec<String>, active: usize, theme: &Theme) -> Tabs<'a> {
+    let titles = titles
+        .into_iter()
+        .enumerate()
+        .map(|(idx, title)| Spans::from(format!("{}:{}", idx + 1, title)))
+        .collect();
+
+    Tabs::new(titles)
+        .select(active)
+        .style(Style::default().fg(theme.text))
+        .highlight_style(theme.highlight_style())
+}
+
+// draws the listing of `pane` into `area` and returns the number of visible rows;
+// `nodes` are what `files` came from in tree mode
+pub fn draw_listing<B: Backend>(f: &mut Frame<B>, area: Rect, pane: &mut Pane,
+                                files: &[FileData], nodes: &[TreeNode], config: &Config) -> usize {
+    let title = match &pane.listing.filter {
+        Some(filter) => format!(" {} [{}] ", pane.path.to_string_lossy(), filter),
+        None => format!(" {} ", pane.path.to_string_lossy()),
+    };
+
+    if pane.tree.is_some() {
+        let list = tree::render_tree(title, nodes, &pane.selection, config);
+        f.render_stateful_widget(list, area, &mut pane.marked_file);
+        area.height.saturating_sub(2) as usize
+    } else if config.layout.long_listing {
+        columns::render_table(f, area, pane, files, title, true, config);
+        area.height.saturating_sub(3) as usize
+    } else {
+        let list = render_file_list(title, files, &pane.selection, config);
+        f.render_stateful_widget(list, area, &mut pane.marked_file);
+        area.height.saturating_sub(2) as usize
+    }
+}
+
+pub fn draw_split<B: Backend>(f: &mut Frame<B>, area: Rect, pane: &mut Pane,
+                              files: &[FileData], nodes: &[TreeNode], config: &Config) -> usize {
+    let main_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(config.layout.list_width),
+                Constraint::Percentage(100 - config.layout.list_width),
+            ].as_ref()
+        )
+        .split(area);
+
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(config.layout.preview_height),
+                Constraint::Length(config.layout.info_height),
+                Constraint::Length(2)
+            ].as_ref()
+        )
+        .split(main_chunks[1]);
+
+    let list_height = draw_listing(f, main_chunks[0], pane, files, nodes, config);
+
+    let selected_file = pane.marked_file.selected().and_then(|idx| files.get(idx));
+    match selected_file {
+        Some(dir) if dir.is_dir() => draw_dir_preview(f, right[0], pane, dir, config),
+        _ => {
+            let preview = render_preview(selected_file, &mut pane.preview, right[0], &config.theme);
+            f.render_widget(preview, right[0]);
+        }
+    }
+    // after the preview, which finds out what goes in here for images
+    f.render_widget(render_info(selected_file, &pane.preview, &config.theme), right[1]);
+
+    list_height
+}
@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::Style;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph};
+use tui::Frame;
+
+use super::centered_rect;
+use crate::opener::Rule;
+use crate::theme::Theme;
+
+// the rules matching `file`, to pick one other than the first
+pub struct OpenWithPopup {
+    pub file: PathBuf,
+    pub rules: Vec<Rule>,
+    pub state: ListState,
+}
+
+impl OpenWithPopup {
+    pub fn new(file: PathBuf, rules: Vec<Rule>) -> OpenWithPopup {
+        let mut state = ListState::default();
+        state.select(Some(0));
+
+        OpenWithPopup { file, rules, state }
+    }
+
+    pub fn selected(&self) -> Option<&Rule> {
+        self.rules.get(self.state.selected()?)
+    }
+
+    pub fn move_selection(&mut self, offset: isize) {
+        if self.rules.is_empty() {
+            return;
+        }
+
+        let selected = self.state.selected().unwrap_or(0) as isize;
+        let idx = (selected + offset).rem_euclid(self.rules.len() as isize);
+        self.state.select(Some(idx as usize));
+    }
+}
+
+pub fn draw_open_with<B: Backend>(f: &mut Frame<B>, area: Rect, popup: &mut OpenWithPopup,
+                                  theme: &Theme) {
+    let area = centered_rect(60, 50, area);
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)].as_ref())
+        .margin(1)
+        .split(area);
+
+    let name = popup.file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().fg(theme.text))
+        .title(format!(" Open {} with ", name))
+        .border_style(Style::default().fg(theme.list_border))
+        .border_type(BorderType::Thick);
+    f.render_widget(block, area);
+
+    let items: Vec<_> = popup.rules
+        .iter()
+        .map(|rule| {
+            let kind = if rule.terminal { "term" } else { "gui " };
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{}  ", kind), Style::default().fg(theme.info_border)),
+                Span::raw(rule.command.clone()),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(theme.highlight_style())
+        .highlight_symbol(">");
+    f.render_stateful_widget(list, chunks[0], &mut popup.state);
+
+    f.render_widget(Paragraph::new("<CR> open  <Esc> close"), chunks[1]);
+}
@@ -0,0 +1,51 @@
+use tui::backend::Backend;
+use tui::layout::Rect;
+use tui::style::Style;
+use tui::widgets::{Block, BorderType, Borders, Clear, Paragraph};
+use tui::Frame;
+
+use super::centered_rect;
+use crate::theme::Theme;
+
+// what a command printed, scrolled a line or a page at a time
+pub struct OutputPane {
+    pub title: String,
+    pub text: String,
+    pub scroll: usize,
+    pub height: usize,
+}
+
+impl OutputPane {
+    pub fn new(title: String, text: String) -> OutputPane {
+        OutputPane { title, text, scroll: 0, height: 0 }
+    }
+
+    pub fn scroll_by(&mut self, offset: isize) {
+        let last = self.text.lines().count().saturating_sub(self.height.max(1));
+        let scroll = self.scroll as isize + offset;
+        self.scroll = scroll.clamp(0, last as isize) as usize;
+    }
+
+    pub fn page(&self) -> isize {
+        self.height.max(1) as isize
+    }
+}
+
+pub fn draw_output<B: Backend>(f: &mut Frame<B>, area: Rect, pane: &mut OutputPane,
+                               theme: &Theme) {
+    let area = centered_rect(80, 80, area);
+    f.render_widget(Clear, area);
+    pane.height = area.height.saturating_sub(2) as usize;
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().fg(theme.text))
+        .title(format!(" {} ", pane.title))
+        .border_style(Style::default().fg(theme.preview_border))
+        .border_type(BorderType::Thick);
+
+    let paragraph = Paragraph::new(pane.text.as_str())
+        .block(block)
+        .scroll((pane.scroll.min(u16::MAX as usize) as u16, 0));
+    f.render_widget(paragraph, area);
+}
@@ -0,0 +1,48 @@
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use tui::style::{Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, BorderType, Borders, List, ListItem};
+
+use super::name_span;
+use crate::config::Config;
+use crate::tree::TreeNode;
+
+pub fn render_tree<'a>(title: String, nodes: &[TreeNode], selection: &BTreeSet<PathBuf>,
+                       config: &Config) -> List<'a> {
+    let theme = &config.theme;
+
+    let tree_block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().fg(theme.text))
+        .title(title)
+        .border_style(Style::default().fg(theme.list_border))
+        .border_type(BorderType::Thick);
+
+    let guide_style = Style::default().fg(theme.text).add_modifier(Modifier::DIM);
+
+    let items: Vec<_> = nodes
+        .iter()
+        .map(|node| {
+            let file = &node.file;
+            let (marker, suffix) = match (file.is_dir(), node.expanded) {
+                (true, true) => ("▾ ", "/"),
+                (true, false) => ("▸ ", "/"),
+                _ => ("  ", ""),
+            };
+
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{}{}", node.guides, marker), guide_style),
+                name_span(format!("{}{}", file.name, suffix),
+                          config.ls_colors.style_for(file, theme),
+                          selection.contains(file.path())),
+            ]))
+        })
+        .collect();
+
+    List::new(items)
+        .block(tree_block)
+        .highlight_style(theme.highlight_style())
+        .highlight_symbol(">")
+}
@@ -0,0 +1,350 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use lzma_rust2::{XzOptions, XzWriter};
+use zip::write::SimpleFileOptions;
+
+use rufile::archive::{self, Format};
+use rufile::command_input::input::{CommandHandler, InputMode};
+use rufile::entry::file_data::FileData;
+use rufile::entry::listing::{read_dir, ListingOptions};
+use rufile::pane::{DualPane, Pane, PreviewState};
+use rufile::preview;
+use rufile::vfs;
+
+mod common;
+use common::TempDir;
+
+
+fn names(files: &[FileData]) -> Vec<String> {
+    files.iter().map(|file| file.name.clone()).collect()
+}
+
+fn file_data(dir: &Path, name: &str) -> FileData {
+    read_dir(dir, &ListingOptions::default())
+        .unwrap()
+        .into_iter()
+        .find(|file| file.name == name)
+        .unwrap()
+}
+
+// `src/` holding `main.rs` (100 bytes, 0755) and `README` (20 bytes)
+fn write_tar<W: Write>(writer: W) -> W {
+    let mut builder = tar::Builder::new(writer);
+
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Directory);
+    header.set_mode(0o755);
+    header.set_mtime(1_600_000_000);
+    header.set_size(0);
+    builder.append_data(&mut header, "src/", &[][..]).unwrap();
+
+    for (name, size, mode) in [("src/main.rs", 100, 0o755), ("README", 20, 0o644)] {
+        let mut header = tar::Header::new_gnu();
+        header.set_mode(mode);
+        header.set_mtime(1_600_000_000);
+        header.set_size(size as u64);
+        builder.append_data(&mut header, name, &vec![b'x'; size][..]).unwrap();
+    }
+
+    builder.into_inner().unwrap()
+}
+
+fn write_archives(dir: &Path) {
+    write_tar(File::create(dir.join("plain.tar")).unwrap());
+    write_tar(GzEncoder::new(File::create(dir.join("gzip.tgz")).unwrap(), Compression::fast()))
+        .finish()
+        .unwrap();
+    write_tar(XzWriter::new(File::create(dir.join("xz.tar.xz")).unwrap(),
+                            XzOptions::with_preset(1)).unwrap())
+        .finish()
+        .unwrap();
+
+    let mut zip = zip::ZipWriter::new(File::create(dir.join("docs.zip")).unwrap());
+    let options = SimpleFileOptions::default().unix_permissions(0o644);
+    zip.add_directory("src/", options.unix_permissions(0o755)).unwrap();
+    zip.start_file("src/main.rs", options.unix_permissions(0o755)).unwrap();
+    zip.write_all(&[b'x'; 100]).unwrap();
+    zip.start_file("README", options).unwrap();
+    zip.write_all(&[b'x'; 20]).unwrap();
+    zip.finish().unwrap();
+}
+
+#[test]
+fn test_archive_formats() {
+    assert_eq!(Some(Format::Tar), Format::from_name("a.tar"));
+    assert_eq!(Some(Format::TarGz), Format::from_name("a.TAR.GZ"));
+    assert_eq!(Some(Format::TarGz), Format::from_name("a.tgz"));
+    assert_eq!(Some(Format::TarXz), Format::from_name("a.tar.xz"));
+    assert_eq!(Some(Format::Zip), Format::from_name("a.zip"));
+    assert_eq!(Some(Format::TarZst), Format::from_name("a.tar.zst"));
+    assert_eq!(None, Format::from_name("a.gz"));
+    assert_eq!(None, Format::from_name("é"));
+    assert_eq!(None, Format::from_name("tar"));
+}
+
+#[test]
+fn test_archive_formats_by_mime() {
+    let dir = TempDir::new("archive-mime");
+    write_archives(&dir);
+    for (name, copy) in [("gzip.tgz", "backup"), ("plain.tar", "bundle"),
+                         ("xz.tar.xz", "snapshot"), ("docs.zip", "docs")] {
+        fs::copy(dir.join(name), dir.join(copy)).unwrap();
+    }
+    // gzip alone isn't an archive
+    let mut gzip = GzEncoder::new(File::create(dir.join("log")).unwrap(), Compression::fast());
+    gzip.write_all(b"just a log\n").unwrap();
+    gzip.finish().unwrap();
+
+    assert_eq!(Some(Format::TarGz), Format::detect(&dir.join("backup")));
+    assert_eq!(Some(Format::Tar), Format::detect(&dir.join("bundle")));
+    assert_eq!(Some(Format::TarXz), Format::detect(&dir.join("snapshot")));
+    assert_eq!(Some(Format::Zip), Format::detect(&dir.join("docs")));
+    assert_eq!(None, Format::detect(&dir.join("log")));
+    assert_eq!(3, archive::entries(&dir.join("backup"), Format::TarGz).unwrap().len());
+}
+
+#[test]
+fn test_archive_entries() {
+    let dir = TempDir::new("archive-entries");
+    write_archives(&dir);
+
+    for name in ["plain.tar", "gzip.tgz", "xz.tar.xz", "docs.zip"] {
+        let file = file_data(&dir, name);
+        let format = Format::detect(file.path()).unwrap();
+        let entries = archive::entries(file.path(), format).unwrap();
+
+        let listed: Vec<(&str, u64, String)> = entries
+            .iter()
+            .map(|entry| (entry.path.as_str(), entry.size, entry.mode_string()))
+            .collect();
+        assert_eq!(vec![
+            ("src", 0, String::from("drwxr-xr-x")),
+            ("src/main.rs", 100, String::from("-rwxr-xr-x")),
+            ("README", 20, String::from("-rw-r--r--")),
+        ], listed, "{}", name);
+        assert!(entries[0].is_dir());
+        assert_eq!(120, archive::total_size(&entries));
+    }
+}
+
+#[test]
+fn test_archive_preview() {
+    let dir = TempDir::new("archive-preview");
+    write_archives(&dir);
+    let file = file_data(&dir, "gzip.tgz");
+
+    let mut state = PreviewState::default();
+    let lines = preview::file_preview(&file, &mut state, 80, 2);
+    assert_eq!(Some("3 entries, 120 B"), state.header.as_deref());
+    assert_eq!(2, lines.len());
+
+    let text: String = lines[1].0.iter().map(|span| span.content.as_ref()).collect();
+    assert!(text.starts_with("-rwxr-xr-x     100 B  "));
+    assert!(text.ends_with("  src/main.rs"));
+
+    state.scroll_by(10);
+    assert_eq!(2, preview::file_preview(&file, &mut state, 80, 2).len());
+    assert_eq!(1, state.scroll);
+
+    // forcing hex shows the compressed bytes
+    state.hex = true;
+    preview::file_preview(&file, &mut state, 80, 2);
+    assert_eq!(None, state.header);
+}
+
+#[test]
+fn test_archive_listing() {
+    let dir = TempDir::new("archive-listing");
+    write_archives(&dir);
+
+    // directories only implied by their contents are listed too
+    let mut builder = tar::Builder::new(File::create(dir.join("implied.tar")).unwrap());
+    let mut header = tar::Header::new_gnu();
+    header.set_mode(0o644);
+    header.set_size(3);
+    builder.append_data(&mut header, "a/b/c.txt", &b"abc"[..]).unwrap();
+    builder.finish().unwrap();
+
+    let options = ListingOptions::default();
+    for name in ["plain.tar", "docs.zip"] {
+        let root = dir.join(name);
+        assert!(vfs::in_archive(&root));
+        assert!(!vfs::is_member(&root));
+        assert!(vfs::is_member(&root.join("src/main.rs")));
+
+        let files = read_dir(&root, &options).unwrap();
+        assert_eq!(vec!["src", "README"], names(&files));
+        assert!(files[0].is_dir());
+        assert_eq!(root.join("src"), files[0].path());
+
+        let src = read_dir(&root.join("src"), &options).unwrap();
+        assert_eq!(vec!["main.rs"], names(&src));
+        assert_eq!(100, src[0].size());
+        assert_eq!("-rwxr-xr-x", src[0].mode_string());
+
+        assert!(read_dir(&root.join("README"), &options).is_err());
+        assert!(read_dir(&root.join("missing"), &options).is_err());
+    }
+
+    let implied = dir.join("implied.tar");
+    assert_eq!(vec!["a"], names(&read_dir(&implied, &options).unwrap()));
+    assert_eq!(vec!["c.txt"], names(&read_dir(&implied.join("a/b"), &options).unwrap()));
+    assert_eq!(Some(3), vfs::total_size(&implied.join("a")));
+
+    assert!(!vfs::in_archive(&dir));
+}
+
+#[test]
+fn test_copy_out_of_archive() {
+    let dir = TempDir::new("archive-copy");
+    write_archives(&dir);
+    fs::create_dir(dir.join("out")).unwrap();
+
+    let mut pane = Pane::new(dir.to_path_buf(), ListingOptions::default());
+    pane.enter_dir("gzip.tgz").unwrap();
+    assert_eq!(dir.join("gzip.tgz"), pane.path);
+    assert_eq!(dir.join("gzip.tgz"), vfs::current_dir().unwrap());
+    pane.enter_dir("src").unwrap();
+    pane.go_parent();
+    assert_eq!(Some(String::from("src")), pane.selected_name());
+
+    // nothing is written inside
+    let mut command = CommandHandler::default();
+    command.run(":d", Some("README"));
+    assert_eq!(InputMode::Error, command.input_mode);
+    command.run(":n f new.txt", None);
+    assert_eq!(InputMode::Error, command.input_mode);
+    assert!(!dir.join("README").exists() && !dir.join("new.txt").exists());
+
+    command.run(":c", Some("src"));
+    assert_eq!(InputMode::Normal, command.input_mode);
+    command.run(":p", None);
+    assert_eq!(InputMode::Error, command.input_mode);
+
+    pane.change_dir(dir.join("out")).unwrap();
+    command.run(":p", None);
+    assert_eq!(InputMode::Normal, command.input_mode);
+    let main = dir.join("out/src/main.rs");
+    assert_eq!(vec![b'x'; 100], fs::read(&main).unwrap());
+    assert_eq!(0o755, fs::metadata(&main).unwrap().permissions().mode() & 0o777);
+
+    // and between panes
+    let mut panes = DualPane::new(dir.join("out"), ListingOptions::default());
+    panes.switch().unwrap();
+    panes.active_mut().change_dir(dir.join("docs.zip")).unwrap();
+    command.transfer(Some("README"), &dir.join("out"), true);
+    assert_eq!(InputMode::Error, command.input_mode);
+    command.transfer(Some("README"), &dir.join("out"), false);
+    assert_eq!(InputMode::Normal, command.input_mode);
+    assert_eq!(20, fs::metadata(dir.join("out/README")).unwrap().len());
+
+    panes.switch().unwrap();
+}
+
+#[test]
+fn test_create_and_unpack() {
+    let dir = TempDir::new("archive-create");
+    fs::create_dir_all(dir.join("project/src")).unwrap();
+    fs::write(dir.join("project/src/main.rs"), "fn main() {}\n").unwrap();
+    fs::set_permissions(dir.join("project/src/main.rs"), fs::Permissions::from_mode(0o755))
+        .unwrap();
+    fs::write(dir.join("notes.txt"), "notes\n").unwrap();
+    std::os::unix::fs::symlink("notes.txt", dir.join("project/link")).unwrap();
+    let paths = vec![dir.join("project"), dir.join("notes.txt")];
+
+    assert_eq!(Some(dir.join("a")), archive::unpack_dir(&dir.join("a.tar.gz")));
+    assert_eq!(Some(dir.join("b")), archive::unpack_dir(&dir.join("b.zip")));
+    assert_eq!(None, archive::unpack_dir(&dir.join("backup")));
+
+    for name in ["a.tar", "b.tar.gz", "c.tar.zst", "d.tar.xz", "e.zip"] {
+        let target = dir.join(name);
+        let mut reported = Vec::new();
+        let count = archive::create(&paths, &target, |done, total| reported.push((done, total)))
+            .unwrap();
+        assert_eq!(5, count, "{}", name);
+        assert_eq!((5, 5), *reported.last().unwrap());
+
+        // never over an existing file
+        assert!(archive::create(&paths, &target, |_, _| {}).is_err());
+
+        let format = Format::from_name(name).unwrap();
+        let dest = archive::unpack(&target, format, |_, _| {}).unwrap();
+        assert_eq!(dir.join(&name[..1]), dest);
+        let main = dest.join("project/src/main.rs");
+        assert_eq!("fn main() {}\n", fs::read_to_string(&main).unwrap());
+        assert_eq!(0o755, fs::metadata(&main).unwrap().permissions().mode() & 0o777);
+        assert_eq!("notes\n", fs::read_to_string(dest.join("notes.txt")).unwrap());
+        assert_eq!(Path::new("notes.txt"), fs::read_link(dest.join("project/link")).unwrap());
+
+        // the directory has to be new
+        assert!(archive::unpack(&target, format, |_, _| {}).is_err());
+    }
+
+    assert!(archive::create(&paths, &dir.join("f.rar"), |_, _| {}).is_err());
+    assert!(!dir.join("f.rar").exists());
+}
+
+// a tar header with whatever name, bypassing the checks of `tar::Builder`
+fn raw_header(name: &str, entry_type: tar::EntryType, size: u64) -> tar::Header {
+    let mut header = tar::Header::new_old();
+    header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+    header.set_entry_type(entry_type);
+    header.set_mode(0o644);
+    header.set_size(size);
+    header.set_cksum();
+    header
+}
+
+#[test]
+fn test_unpack_stays_inside() {
+    let dir = TempDir::new("archive-traversal");
+    let outside = dir.join("outside");
+    fs::create_dir(&outside).unwrap();
+
+    let mut builder = tar::Builder::new(File::create(dir.join("evil.tar")).unwrap());
+    builder.append(&raw_header("../escaped.txt", tar::EntryType::Regular, 1), &b"x"[..]).unwrap();
+    let absolute = outside.join("absolute.txt");
+    builder.append(&raw_header(absolute.to_str().unwrap(), tar::EntryType::Regular, 1), &b"x"[..])
+        .unwrap();
+    let mut link = raw_header("link", tar::EntryType::Symlink, 0);
+    link.set_link_name(&outside).unwrap();
+    link.set_cksum();
+    builder.append(&link, &[][..]).unwrap();
+    builder.append(&raw_header("link/through.txt", tar::EntryType::Regular, 1), &b"x"[..]).unwrap();
+    builder.append(&raw_header("fine.txt", tar::EntryType::Regular, 1), &b"x"[..]).unwrap();
+    builder.finish().unwrap();
+
+    let dest = archive::unpack(&dir.join("evil.tar"), Format::Tar, |_, _| {}).unwrap();
+    assert_eq!(dir.join("evil"), dest);
+    assert!(dest.join("fine.txt").exists());
+    assert!(!dir.join("escaped.txt").exists());
+    assert!(!absolute.exists());
+    assert!(!outside.join("through.txt").exists());
+}
+
+#[test]
+fn test_tar_hard_links_are_left_out() {
+    let dir = TempDir::new("archive-hardlink");
+    let mut builder = tar::Builder::new(File::create(dir.join("links.tar")).unwrap());
+    builder.append(&raw_header("data.txt", tar::EntryType::Regular, 1), &b"x"[..]).unwrap();
+    let mut link = raw_header("again.txt", tar::EntryType::Link, 0);
+    link.set_link_name("data.txt").unwrap();
+    link.set_cksum();
+    builder.append(&link, &[][..]).unwrap();
+    builder.append(&raw_header("pipe", tar::EntryType::Fifo, 0), &[][..]).unwrap();
+    builder.finish().unwrap();
+
+    // listed as what extracting writes
+    let entries = archive::entries(&dir.join("links.tar"), Format::Tar).unwrap();
+    let listed: Vec<&str> = entries.iter().map(|entry| entry.path.as_str()).collect();
+    assert_eq!(vec!["data.txt"], listed);
+
+    let dest = archive::unpack(&dir.join("links.tar"), Format::Tar, |_, _| {}).unwrap();
+    assert!(dest.join("data.txt").exists());
+    assert!(!dest.join("again.txt").exists());
+}
@@ -0,0 +1,48 @@
+use std::path::Path;
+
+use rufile::bookmarks::Bookmarks;
+use rufile::command_input::input::CommandHandler;
+use rufile::keymap::Action;
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_bookmarks_file() {
+    let dir = TempDir::new("bookmarks");
+    let file = dir.join("rufile/bookmarks.toml");
+
+    let mut bookmarks = Bookmarks::load(file.clone());
+    assert_eq!(0, bookmarks.entries().count());
+    bookmarks.set("a", Path::new("/tmp")).unwrap();
+    bookmarks.set("projects", Path::new("/home/user/src")).unwrap();
+    bookmarks.set("a", Path::new("/usr")).unwrap();
+
+    let bookmarks = Bookmarks::load(file.clone());
+    assert_eq!(Some(Path::new("/usr")), bookmarks.get("a"));
+    assert_eq!(vec!["a", "projects"],
+               bookmarks.entries().map(|(name, _)| name).collect::<Vec<_>>());
+
+    let mut bookmarks = bookmarks;
+    assert!(bookmarks.rename("projects", "a").is_err());
+    bookmarks.rename("projects", "src").unwrap();
+    bookmarks.remove("a").unwrap();
+
+    let bookmarks = Bookmarks::load(file);
+    assert_eq!(None, bookmarks.get("a"));
+    assert_eq!(None, bookmarks.get("projects"));
+    assert_eq!(Some(Path::new("/home/user/src")), bookmarks.get("src"));
+}
+
+#[test]
+fn test_mark_commands() {
+    let mut command = CommandHandler::default();
+
+    command.run(":mark work", None);
+    assert_eq!(Some((Action::Mark, None)), command.take_action());
+    assert_eq!(Some(String::from("work")), command.take_argument());
+
+    command.run(":jump a", None);
+    assert_eq!(Some((Action::Jump, None)), command.take_action());
+    assert_eq!(Some(String::from("a")), command.take_argument());
+}
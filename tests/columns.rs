@@ -0,0 +1,86 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+use users::UsersCache;
+
+use rufile::config::Config;
+use rufile::entry::file_data::FileData;
+use rufile::view::columns::{fit_columns, Column, TableView, DEFAULT_COLUMNS};
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_column_names() {
+    assert_eq!(Ok(Column::Modified), "mtime".parse());
+    assert_eq!(Ok(Column::Links), "links".parse());
+    assert!("color".parse::<Column>().is_err());
+}
+
+#[test]
+fn test_configured_columns() {
+    let config = Config::parse("").unwrap();
+    assert_eq!(DEFAULT_COLUMNS.to_vec(), config.layout.columns);
+    assert!(!config.layout.long_listing);
+
+    let config = Config::parse("[layout]\nlong_listing = true\ncolumns = [\"inode\", \"name\", \"mime\"]").unwrap();
+    assert!(config.layout.long_listing);
+    assert_eq!(vec![Column::Inode, Column::Name, Column::Mime], config.layout.columns);
+
+    let err = Config::parse("[layout]\ncolumns = [\"name\", \"owner2\"]").unwrap_err();
+    assert!(err.to_string().contains("unknown column `owner2`"));
+    assert!(Config::parse("[layout]\ncolumns = []").is_err());
+}
+
+#[test]
+fn test_fit_columns() {
+    let columns = [Column::Mode, Column::Owner, Column::Size, Column::Inode, Column::Name];
+    let widths = [10, 8, 8, 7, 30];
+
+    // 10 + 8 + 8 + 7 + 12 and four gaps
+    assert_eq!(vec![0, 1, 2, 3, 4], fit_columns(&columns, &widths, 49));
+    assert_eq!(vec![0, 1, 2, 4], fit_columns(&columns, &widths, 48));
+    assert_eq!(vec![0, 2, 4], fit_columns(&columns, &widths, 33));
+    assert_eq!(vec![2, 4], fit_columns(&columns, &widths, 22));
+    assert_eq!(vec![4], fit_columns(&columns, &widths, 5));
+}
+
+#[test]
+fn test_column_text() {
+    let dir = TempDir::new("columns");
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(dir.join("file"), "12345").unwrap();
+    fs::set_permissions(dir.join("file"), fs::Permissions::from_mode(0o640)).unwrap();
+    fs::hard_link(dir.join("file"), dir.join("link")).unwrap();
+
+    let users = UsersCache::new();
+    let file = |name: &str| {
+        let entry = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .find(|entry| entry.file_name() == name)
+            .unwrap();
+        FileData::new(entry).unwrap()
+    };
+
+    assert_eq!("-rw-r-----", Column::Mode.text(&file("file"), &users));
+    assert_eq!("2", Column::Links.text(&file("file"), &users));
+    assert_eq!("5 B", Column::Size.text(&file("file"), &users));
+    assert_eq!("sub/", Column::Name.text(&file("sub"), &users));
+    assert_eq!('d', Column::Mode.text(&file("sub"), &users).chars().next().unwrap());
+}
+
+#[test]
+fn test_table_window() {
+    let mut view = TableView::default();
+    assert_eq!(0..10, view.window(Some(0), 10, 100));
+    assert_eq!(0..10, view.window(Some(9), 10, 100));
+    // the window follows the cursor only once it leaves
+    assert_eq!(3..13, view.window(Some(12), 10, 100));
+    assert_eq!(3..13, view.window(Some(5), 10, 100));
+    assert_eq!(2..12, view.window(Some(2), 10, 100));
+    // without a cursor it stays where it was
+    assert_eq!(2..12, view.window(None, 10, 100));
+    assert_eq!(0..5, view.window(None, 10, 5));
+    assert_eq!(0..0, view.window(None, 10, 0));
+}
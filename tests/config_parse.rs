@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use termion::event::Key;
+use tui::style::Color;
+
+use rufile::cli::Args;
+use rufile::config::Config;
+use rufile::entry::listing::SortKey;
+use rufile::keymap::{parse_key, Action};
+use rufile::theme::{parse_color, Theme};
+
+#[test]
+fn test_default_config() {
+    let config = Config::parse("").unwrap();
+
+    assert_eq!(Duration::from_millis(200), config.tick_rate);
+    assert_eq!(50, config.layout.list_width);
+    assert_eq!(12, config.layout.preview_height);
+    assert_eq!(6, config.layout.info_height);
+    assert!(!config.restore_tabs);
+    assert_eq!(Color::Yellow, config.theme.list_border);
+    assert_eq!(Some(Action::Quit), config.keymap.action(&[Key::Char('q')]));
+    assert_eq!(Some(Action::Open), config.keymap.action(&[Key::Right]));
+}
+
+#[test]
+fn test_full_config() {
+    let config = Config::parse(r##"
+        [general]
+        tick_rate = 50
+        show_hidden = false
+        sort = "size"
+        sort_reverse = true
+        restore_tabs = true
+
+        [layout]
+        list_width = 40
+        preview_height = 20
+
+        [theme]
+        list_border = "light-cyan"
+        directory = "#ff8000"
+        highlight_bg = "236"
+
+        [keys]
+        x = "quit"
+        q = "none"
+        "<C-n>" = "down"
+    "##).unwrap();
+
+    assert_eq!(Duration::from_millis(50), config.tick_rate);
+    assert!(!config.listing.show_hidden);
+    assert_eq!(SortKey::Size, config.listing.sort);
+    assert!(config.listing.reverse);
+    assert!(config.restore_tabs);
+    assert_eq!(40, config.layout.list_width);
+    assert_eq!(20, config.layout.preview_height);
+    assert_eq!(6, config.layout.info_height);
+    assert_eq!(Color::LightCyan, config.theme.list_border);
+    assert_eq!(Color::Rgb(255, 128, 0), config.theme.directory);
+    assert_eq!(Color::Indexed(236), config.theme.highlight_bg);
+    assert_eq!(Some(Action::Quit), config.keymap.action(&[Key::Char('x')]));
+    assert_eq!(None, config.keymap.action(&[Key::Char('q')]));
+    assert_eq!(Some(Action::Down), config.keymap.action(&[Key::Ctrl('n')]));
+
+    let config = Config::parse("[theme]\nname = \"nord\"\nlink = \"red\"").unwrap();
+    assert_eq!(Theme::named("nord").unwrap().directory, config.theme.directory);
+    assert_eq!(Color::Red, config.theme.link);
+}
+
+#[test]
+fn test_invalid_config() {
+    let err = |content: &str| Config::parse(content).unwrap_err().to_string();
+
+    assert!(err("[general]\ntick_rate = 0").contains("general.tick_rate"));
+    assert!(err("[general]\nsort = \"color\"").contains("unknown sort key `color`"));
+    assert!(err("[general]\nunknown = 1").contains("unknown field `unknown`"));
+    assert!(err("[layout]\nlist_width = 95").contains("layout.list_width"));
+    assert!(err("[layout]\npreview_height = 0").contains("layout.preview_height"));
+    assert!(err("[layout]\ninfo_height = 200").contains("layout.info_height: must be between 3 and 50"));
+    assert!(err("[theme]\nlist_border = \"yelow\"").contains("unknown color `yelow`"));
+    assert!(err("[theme]\nborder = \"red\"").contains("unknown color slot `border`"));
+    assert!(err("[theme]\ndirectory = \"#aébcd\"").contains("invalid hex color `#aébcd`"));
+    assert!(err("[theme]\nname = \"neon\"").contains("unknown theme `neon`"));
+    assert!(err("[keys]\nx = \"teleport\"").contains("unknown action `teleport`"));
+    assert!(err("[keys]\n\"<X-y>\" = \"quit\"").contains("invalid key `<X-y>`"));
+}
+
+#[test]
+fn test_parse_key() {
+    assert_eq!(Ok(Key::Char('j')), parse_key("j"));
+    assert_eq!(Ok(Key::Ctrl('d')), parse_key("<C-d>"));
+    assert_eq!(Ok(Key::Alt('x')), parse_key("<A-x>"));
+    assert_eq!(Ok(Key::PageDown), parse_key("<PageDown>"));
+    assert_eq!(Ok(Key::F(5)), parse_key("<F5>"));
+    assert_eq!(Ok(Key::Char('\n')), parse_key("<CR>"));
+    assert_eq!(Ok(Key::BackTab), parse_key("<S-Tab>"));
+    assert!(parse_key("<F13>").is_err());
+    assert!(parse_key("abc").is_err());
+}
+
+#[test]
+fn test_parse_color() {
+    assert_eq!(Ok(Color::DarkGray), parse_color("dark_gray"));
+    assert_eq!(Ok(Color::Rgb(0, 16, 255)), parse_color("#0010FF"));
+    assert!(parse_color("#12345").is_err());
+    // six bytes, but not six hex digits
+    assert!(parse_color("#aébcd").is_err());
+    assert!(parse_color("#+1+2+3").is_err());
+    assert!(parse_color("256").is_err());
+}
+
+#[test]
+fn test_cli_args() {
+    let parse = |args: &[&str]| Args::parse(args.iter().map(|arg| arg.to_string()));
+
+    assert_eq!(Ok(Args::default()), parse(&[]));
+    assert_eq!(Some("a.toml".into()), parse(&["--config", "a.toml"]).unwrap().config);
+    assert_eq!(Some("b.toml".into()), parse(&["--config=b.toml"]).unwrap().config);
+    assert!(parse(&["--config"]).is_err());
+    assert!(parse(&["--bogus"]).is_err());
+}
+
+#[test]
+fn test_cli_choosers() {
+    let parse = |args: &[&str]| Args::parse(args.iter().map(|arg| arg.to_string()));
+
+    let args = parse(&["--choosedir", "/tmp/dir", "--choosefiles=-"]).unwrap();
+    assert_eq!(Some("/tmp/dir".into()), args.choosedir);
+    assert_eq!(Some((&"-".into(), true)), args.chooser());
+
+    let args = parse(&["--choosefile", "out"]).unwrap();
+    assert_eq!(Some((&"out".into(), false)), args.chooser());
+    assert_eq!(None, parse(&[]).unwrap().chooser());
+
+    assert!(parse(&["--choosefile"]).is_err());
+    assert!(parse(&["--choosefile=a", "--choosefiles=b"]).is_err());
+    assert!(parse(&["-c=a.toml"]).is_err());
+}
@@ -0,0 +1,43 @@
+use std::env;
+use std::fs;
+
+use rufile::command_input::input::{CommandHandler, InputMode};
+use rufile::entry::listing::ListingOptions;
+use rufile::pane::DualPane;
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_transfer_between_panes() {
+    let dir = TempDir::new("dual");
+    fs::create_dir_all(dir.join("left/sub")).unwrap();
+    fs::create_dir_all(dir.join("right")).unwrap();
+    fs::write(dir.join("left/a.txt"), "a").unwrap();
+    fs::write(dir.join("left/sub/b.txt"), "b").unwrap();
+
+    let mut panes = DualPane::new(dir.join("right"), ListingOptions::default());
+    panes.switch().unwrap();
+    panes.active_mut().change_dir(dir.join("left")).unwrap();
+    assert_eq!(1, panes.active);
+    assert_eq!(dir.join("right"), panes.other().path);
+
+    let mut command = CommandHandler::default();
+    command.transfer(Some("a.txt"), &dir.join("right"), false);
+    assert_eq!(InputMode::Normal, command.input_mode);
+    assert_eq!("a", fs::read_to_string(dir.join("right/a.txt")).unwrap());
+    assert!(dir.join("left/a.txt").exists());
+
+    // never overwrite what is already there
+    command.transfer(Some("a.txt"), &dir.join("right"), true);
+    assert_eq!(InputMode::Error, command.input_mode);
+    assert!(dir.join("left/a.txt").exists());
+
+    command.transfer(Some("sub"), &dir.join("right"), true);
+    assert_eq!(InputMode::Normal, command.input_mode);
+    assert_eq!("b", fs::read_to_string(dir.join("right/sub/b.txt")).unwrap());
+    assert!(!dir.join("left/sub").exists());
+
+    panes.switch().unwrap();
+    assert_eq!(dir.join("right"), env::current_dir().unwrap());
+}
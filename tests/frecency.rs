@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rufile::frecency::Frecency;
+
+mod common;
+use common::TempDir;
+
+const NOW: u64 = 1_700_000_000;
+const DAY: u64 = 24 * 60 * 60;
+
+fn paths(matches: Vec<(PathBuf, f64)>) -> Vec<PathBuf> {
+    matches.into_iter().map(|(path, _)| path).collect()
+}
+
+#[test]
+fn test_ranking() {
+    let dir = TempDir::new("frecency");
+    let file = dir.join("frecency");
+
+    let mut frecency = Frecency::load(file.clone());
+    for _ in 0..3 {
+        frecency.add(Path::new("/home/user/src/rufile"), NOW - 10 * DAY).unwrap();
+    }
+    frecency.add(Path::new("/home/user/src/rufile/src"), NOW - 2 * 60 * 60).unwrap();
+    frecency.add(Path::new("/srv/www"), NOW).unwrap();
+    frecency.save().unwrap();
+
+    // recent visits outweigh older, more frequent ones
+    let frecency = Frecency::load(file);
+    assert_eq!(vec![PathBuf::from("/srv/www"), PathBuf::from("/home/user/src/rufile/src"),
+                    PathBuf::from("/home/user/src/rufile")],
+               paths(frecency.query(&[], NOW)));
+
+    // the last fragment has to match the final component
+    assert_eq!(vec![PathBuf::from("/home/user/src/rufile")], paths(frecency.query(&["rufile"], NOW)));
+    assert_eq!(vec![PathBuf::from("/home/user/src/rufile/src")],
+               paths(frecency.query(&["RUF", "src"], NOW)));
+    assert!(frecency.query(&["src", "user"], NOW).is_empty());
+}
+
+#[test]
+fn test_import_autojump() {
+    let dir = TempDir::new("autojump");
+    fs::create_dir_all(dir.join("docs")).unwrap();
+    fs::create_dir_all(dir.join("music")).unwrap();
+    let autojump = dir.join("autojump.txt");
+    fs::write(&autojump, format!("10.0\t{0}/docs\n22.5\t{0}/music\nbroken line\n", dir.display())).unwrap();
+
+    let mut frecency = Frecency::default();
+    assert_eq!(2, frecency.import_autojump(&autojump, NOW).unwrap());
+    assert_eq!(vec![dir.join("music"), dir.join("docs")], paths(frecency.query(&[], NOW)));
+
+    assert_eq!(Some(dir.join("docs")), frecency.find(&["d"], &dir, NOW));
+    // never the directory we are already in
+    assert_eq!(None, frecency.find(&["music"], &dir.join("music"), NOW));
+}
+
+#[test]
+fn test_non_finite_ranks_are_skipped() {
+    let dir = TempDir::new("frecency-nan");
+    let file = dir.join("frecency");
+    fs::write(&file, "/a|nan|1700000000\n/b|inf|1700000000\n/c|2|1700000000\n").unwrap();
+    let autojump = dir.join("autojump.txt");
+    fs::write(&autojump, "NaN\t/d\n-inf\t/e\n3\t/f\n").unwrap();
+
+    let mut frecency = Frecency::load(file.clone());
+    assert_eq!(vec![PathBuf::from("/c")], paths(frecency.query(&[], NOW)));
+    assert_eq!(1, frecency.import_autojump(&autojump, NOW).unwrap());
+    // ageing still works with the rest
+    frecency.add(Path::new("/c"), NOW).unwrap();
+    frecency.save().unwrap();
+    assert_eq!(vec![PathBuf::from("/c"), PathBuf::from("/f")], paths(Frecency::load(file).query(&[], NOW)));
+}
+
+#[test]
+fn test_visits_are_saved_in_batches() {
+    let dir = TempDir::new("frecency-batch");
+    let file = dir.join("frecency");
+
+    let mut frecency = Frecency::load(file.clone());
+    frecency.add(Path::new("/a"), NOW).unwrap();
+    assert!(!file.exists());
+
+    for n in 0..19 {
+        frecency.add(Path::new(&format!("/b{}", n)), NOW).unwrap();
+    }
+    assert_eq!(20, Frecency::load(file.clone()).query(&[], NOW).len());
+
+    frecency.add(Path::new("/c"), NOW).unwrap();
+    assert_eq!(20, Frecency::load(file.clone()).query(&[], NOW).len());
+    frecency.save().unwrap();
+    assert_eq!(21, Frecency::load(file).query(&[], NOW).len());
+}
@@ -0,0 +1,97 @@
+use std::env;
+use std::thread;
+use std::time::Duration;
+
+use rufile::command_input::input::CommandHandler;
+use rufile::jobs::{JobStatus, Jobs};
+use rufile::keymap::Action;
+use rufile::shell;
+
+#[test]
+fn test_shell_shortcuts() {
+    let mut command = CommandHandler::default();
+
+    command.input = ":!grep -n foo %s".to_string();
+    command.exec(None);
+    assert_eq!(Some((Action::Shell, None)), command.take_action());
+    assert_eq!(Some(String::from("grep -n foo %s")), command.take_argument());
+
+    command.input = ":& make ".to_string();
+    command.exec(None);
+    assert_eq!(Some((Action::ShellBackground, None)), command.take_action());
+    assert_eq!(Some(String::from("make")), command.take_argument());
+
+    command.input = ":shell_background make".to_string();
+    command.exec(None);
+    assert_eq!(Some((Action::ShellBackground, None)), command.take_action());
+    assert_eq!(Some(String::from("make")), command.take_argument());
+}
+
+#[test]
+fn test_capture() {
+    let dir = env::temp_dir();
+
+    let (status, output) = shell::capture("echo out; echo err >&2; exit 3", &dir).unwrap();
+    assert_eq!("exit 3", shell::describe(&status));
+    assert_eq!("out\nerr\n", output);
+
+    let (status, output) = shell::capture("pwd", &dir).unwrap();
+    assert!(status.success());
+    assert_eq!(dir.canonicalize().unwrap(), std::path::PathBuf::from(output.trim()));
+}
+
+#[test]
+fn test_background_jobs() {
+    let mut jobs = Jobs::default();
+    assert_eq!(1, jobs.spawn_shell(String::from("echo done"), env::temp_dir()));
+    assert_eq!(2, jobs.spawn_shell(String::from("exit 1"), env::temp_dir()));
+
+    let mut finished = Vec::new();
+    for _ in 0..200 {
+        finished.extend(jobs.finished().iter().map(|job| (job.id, job.status(), job.output())));
+        if finished.len() == 2 {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    finished.sort_by_key(|(id, _, _)| *id);
+
+    let done = JobStatus::Finished { success: true, summary: String::from("exit 0") };
+    let failed = JobStatus::Finished { success: false, summary: String::from("exit 1") };
+    assert_eq!(vec![(1, done, String::from("done\n")), (2, failed, String::new())], finished);
+    // each one is reported once
+    assert!(jobs.finished().is_empty());
+    assert_eq!(2, jobs.len());
+}
+
+#[test]
+fn test_job_progress() {
+    let mut jobs = Jobs::default();
+    let (tx, rx) = std::sync::mpsc::channel::<()>();
+    jobs.spawn(String::from("count"), move |progress| {
+        progress.set(1, 3);
+        rx.recv().ok();
+        (true, String::from("done"), String::new())
+    });
+
+    let job = jobs.get(0).unwrap();
+    for _ in 0..200 {
+        if job.progress().is_some() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(Some((1, 3)), job.progress());
+    assert_eq!(JobStatus::Running, job.status());
+    tx.send(()).unwrap();
+}
+
+#[test]
+fn test_subshell_level() {
+    env::set_var("RUFILE_LEVEL", "2");
+    assert_eq!(2, shell::level());
+    env::set_var("RUFILE_LEVEL", "two");
+    assert_eq!(0, shell::level());
+    env::remove_var("RUFILE_LEVEL");
+    assert_eq!(0, shell::level());
+}
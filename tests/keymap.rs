@@ -0,0 +1,119 @@
+use termion::event::Key;
+
+use rufile::command_input::input::{CommandHandler, InputMode};
+use rufile::keymap::{decode_sequence, parse_key, parse_keys, Action, KeyBuffer, KeyMap, KeyResult};
+
+fn feed_str(keymap: &KeyMap, buffer: &mut KeyBuffer, keys: &str) -> KeyResult {
+    let mut result = KeyResult::Unbound;
+    for key in parse_keys(keys).unwrap() {
+        result = keymap.feed(buffer, key);
+    }
+    result
+}
+
+#[test]
+fn test_parse_key_sequence() {
+    assert_eq!(Ok(vec![Key::Char('g'), Key::Char('g')]), parse_keys("gg"));
+    assert_eq!(Ok(vec![Key::Ctrl('w'), Key::Char('j')]), parse_keys("<C-w>j"));
+    assert_eq!(Ok(vec![Key::Char('<'), Key::Char('x')]), parse_keys("<x"));
+    assert_eq!(Ok(vec![Key::Char('<'), Key::PageUp]), parse_keys("<lt><PageUp>"));
+    assert!(parse_keys("").is_err());
+    assert!(parse_keys("a<Foo>").is_err());
+}
+
+#[test]
+fn test_vim_defaults() {
+    let keymap = KeyMap::default();
+    let mut buffer = KeyBuffer::default();
+
+    assert_eq!(KeyResult::Action(Action::Down, None), feed_str(&keymap, &mut buffer, "j"));
+    assert_eq!(KeyResult::Action(Action::Parent, None), feed_str(&keymap, &mut buffer, "h"));
+    assert_eq!(KeyResult::Action(Action::HalfPageDown, None), feed_str(&keymap, &mut buffer, "<C-d>"));
+    assert_eq!(KeyResult::Action(Action::Bottom, None), feed_str(&keymap, &mut buffer, "G"));
+    assert_eq!(KeyResult::Action(Action::Top, None), feed_str(&keymap, &mut buffer, "<Home>"));
+}
+
+#[test]
+fn test_chords() {
+    let keymap = KeyMap::default();
+    let mut buffer = KeyBuffer::default();
+
+    assert_eq!(KeyResult::Pending, keymap.feed(&mut buffer, Key::Char('g')));
+    assert_eq!("g", buffer.to_string());
+    assert_eq!(KeyResult::Action(Action::Top, None), keymap.feed(&mut buffer, Key::Char('g')));
+    assert!(buffer.is_empty());
+
+    assert_eq!(KeyResult::Action(Action::Copy, None), feed_str(&keymap, &mut buffer, "yy"));
+    assert_eq!(KeyResult::Action(Action::Cut, None), feed_str(&keymap, &mut buffer, "dd"));
+    assert_eq!(KeyResult::Action(Action::Delete, None), feed_str(&keymap, &mut buffer, "dD"));
+
+    assert_eq!(KeyResult::Unbound, feed_str(&keymap, &mut buffer, "gx"));
+    assert!(buffer.is_empty());
+
+    assert_eq!(KeyResult::Pending, feed_str(&keymap, &mut buffer, "d<Esc>"));
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn test_count_prefix() {
+    let keymap = KeyMap::default();
+    let mut buffer = KeyBuffer::default();
+
+    assert_eq!(KeyResult::Action(Action::Down, Some(5)), feed_str(&keymap, &mut buffer, "5j"));
+    assert_eq!(KeyResult::Action(Action::Up, Some(10)), feed_str(&keymap, &mut buffer, "10k"));
+
+    assert_eq!(KeyResult::Pending, feed_str(&keymap, &mut buffer, "12g"));
+    assert_eq!("12g", buffer.to_string());
+    assert_eq!(KeyResult::Action(Action::Top, Some(12)), keymap.feed(&mut buffer, Key::Char('g')));
+
+    assert_eq!(KeyResult::Unbound, feed_str(&keymap, &mut buffer, "0"));
+}
+
+#[test]
+fn test_custom_binding() {
+    let mut keymap = KeyMap::default();
+    let mut buffer = KeyBuffer::default();
+
+    keymap.bind(parse_keys("gh").unwrap(), Action::ToggleHidden);
+    keymap.unbind(&parse_keys("j").unwrap());
+
+    assert_eq!(KeyResult::Action(Action::ToggleHidden, None), feed_str(&keymap, &mut buffer, "gh"));
+    assert_eq!(KeyResult::Unbound, feed_str(&keymap, &mut buffer, "j"));
+}
+
+#[test]
+fn test_action_commands() {
+    let mut command = CommandHandler::default();
+
+    command.input = ":down 3".to_string();
+    command.exec(None);
+    assert_eq!(InputMode::Normal, command.input_mode);
+    assert_eq!(Some((Action::Down, Some(3))), command.take_action());
+    assert_eq!(None, command.take_action());
+
+    command.input = ":half_page_up".to_string();
+    command.exec(None);
+    assert_eq!(Some((Action::HalfPageUp, None)), command.take_action());
+
+    command.input = ":top x".to_string();
+    command.exec(None);
+    assert_eq!(InputMode::Error, command.input_mode);
+    assert_eq!(None, command.take_action());
+
+    command.input = ":teleport".to_string();
+    command.exec(None);
+    assert_eq!(InputMode::Error, command.input_mode);
+    assert_eq!(None, command.take_action());
+}
+
+#[test]
+fn test_alt_arrows() {
+    let keymap = KeyMap::default();
+    let alt_left = parse_key("<A-Left>").unwrap();
+
+    assert_eq!(Some(alt_left), decode_sequence(b"\x1b[1;3D"));
+    assert_eq!(Some(parse_key("<M-right>").unwrap()), decode_sequence(b"\x1b[1;3C"));
+    assert_eq!(None, decode_sequence(b"\x1b[1;5D"));
+    assert_eq!(Some(Action::Back), keymap.action(&[alt_left]));
+    assert_ne!(alt_left, parse_key("<A-l>").unwrap());
+}
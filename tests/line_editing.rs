@@ -0,0 +1,121 @@
+use rufile::command_input::input::{CommandHandler, InputMode};
+
+fn type_str(command: &mut CommandHandler, text: &str) {
+    for c in text.chars() {
+        command.insert(c);
+    }
+}
+
+#[test]
+fn test_cursor_movement() {
+    let mut command = CommandHandler::default();
+    command.start_editing();
+    type_str(&mut command, "r new");
+    assert_eq!(":r new", command.input);
+    assert_eq!(6, command.cursor_column());
+
+    command.move_left();
+    command.move_left();
+    command.insert('x');
+    assert_eq!(":r nxew", command.input);
+
+    command.move_home();
+    assert_eq!(0, command.cursor_column());
+    command.delete();
+    assert_eq!("r nxew", command.input);
+
+    command.move_end();
+    command.backspace();
+    assert_eq!("r nxe", command.input);
+}
+
+#[test]
+fn test_multibyte_editing() {
+    let mut command = CommandHandler::default();
+    command.start_editing();
+    type_str(&mut command, "r żółw");
+    assert_eq!(7, command.cursor_column());
+
+    command.move_left();
+    command.backspace();
+    assert_eq!(":r żów", command.input);
+    assert_eq!(5, command.cursor_column());
+}
+
+#[test]
+fn test_kill_commands() {
+    let mut command = CommandHandler::default();
+    command.start_editing();
+    type_str(&mut command, "n f  file.txt");
+
+    command.delete_word();
+    assert_eq!(":n f  ", command.input);
+    command.delete_word();
+    assert_eq!(":n ", command.input);
+
+    type_str(&mut command, "d dir");
+    command.move_left();
+    command.move_left();
+    command.move_left();
+    command.kill_to_end();
+    assert_eq!(":n d ", command.input);
+
+    command.kill_to_start();
+    assert_eq!("", command.input);
+    assert_eq!(0, command.cursor_column());
+
+    command.backspace();
+    assert_eq!(InputMode::Normal, command.input_mode);
+}
+
+#[test]
+fn test_history_navigation() {
+    let mut command = CommandHandler::default();
+
+    for line in [":r a", ":r b", ":r b", ":x"] {
+        command.history.push(line).unwrap();
+    }
+    assert_eq!(3, command.history.len());
+
+    command.start_editing();
+    type_str(&mut command, "dr");
+
+    command.history_prev();
+    assert_eq!(":x", command.input);
+    command.history_prev();
+    assert_eq!(":r b", command.input);
+    command.history_prev();
+    assert_eq!(":r a", command.input);
+    command.history_prev();
+    assert_eq!(":r a", command.input);
+
+    command.history_next();
+    command.history_next();
+    assert_eq!(":x", command.input);
+    command.history_next();
+    assert_eq!(":dr", command.input);
+}
+
+#[test]
+fn test_history_search() {
+    let mut command = CommandHandler::default();
+
+    for line in [":n f one", ":r two", ":n d three"] {
+        command.history.push(line).unwrap();
+    }
+
+    command.start_editing();
+    command.start_search();
+    assert_eq!(InputMode::Search, command.input_mode);
+
+    command.search_push('n');
+    assert_eq!(Some(":n d three"), command.search_matched());
+    command.search_older();
+    assert_eq!(Some(":n f one"), command.search_matched());
+    command.search_older();
+    assert_eq!(Some(":n f one"), command.search_matched());
+
+    command.accept_search();
+    assert_eq!(InputMode::Editing, command.input_mode);
+    assert_eq!(":n f one", command.input);
+}
@@ -0,0 +1,110 @@
+use std::fs;
+use std::os::unix::fs::{symlink, PermissionsExt};
+
+use tui::style::{Color, Modifier, Style};
+
+use rufile::entry::file_data::FileData;
+use rufile::theme::Theme;
+use rufile::theme::ls_colors::{parse_sgr, LsColors};
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_parse_sgr() {
+    assert_eq!(Style::default(), parse_sgr("0"));
+    assert_eq!(Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD), parse_sgr("01;34"));
+    assert_eq!(Style::default().fg(Color::Yellow).bg(Color::Black), parse_sgr("40;33"));
+    assert_eq!(Style::default().fg(Color::LightRed), parse_sgr("91"));
+    assert_eq!(Style::default().fg(Color::Indexed(208)), parse_sgr("38;5;208"));
+    assert_eq!(Style::default().bg(Color::Rgb(1, 2, 3)), parse_sgr("48;2;1;2;3"));
+}
+
+#[test]
+fn test_parse_ls_colors() {
+    let colors = LsColors::parse("rs=0:di=01;34:ln=01;36:*.tar=01;31:*.TGZ=01;31:*README=33:*.tar=32");
+
+    assert_eq!(Some(parse_sgr("01;34")), colors.kind_style("di"));
+    assert_eq!(None, colors.kind_style("so"));
+    assert_eq!(Some(parse_sgr("32")), colors.suffix_style("backup.tar"));
+    assert_eq!(Some(parse_sgr("01;31")), colors.suffix_style("backup.tgz"));
+    assert_eq!(Some(parse_sgr("33")), colors.suffix_style("README"));
+    assert_eq!(None, colors.suffix_style("tar"));
+}
+
+#[test]
+fn test_parse_dircolors() {
+    let colors = LsColors::parse_dircolors("
+        # comment
+        TERM xterm-256color
+        DIR 01;34 # directories
+        SYMLINK 01;36
+        .zip 01;31
+        *~ 00;90
+    ");
+
+    assert_eq!(Some(parse_sgr("01;34")), colors.kind_style("di"));
+    assert_eq!(Some(parse_sgr("01;36")), colors.kind_style("ln"));
+    assert_eq!(Some(parse_sgr("01;31")), colors.suffix_style("a.ZIP"));
+    assert_eq!(Some(parse_sgr("00;90")), colors.suffix_style("notes.txt~"));
+}
+
+#[test]
+fn test_style_for_file() {
+    let dir = TempDir::new("ls-colors");
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(dir.join("run.sh"), "").unwrap();
+    fs::set_permissions(dir.join("run.sh"), fs::Permissions::from_mode(0o755)).unwrap();
+    fs::write(dir.join("data.tar"), "").unwrap();
+    symlink(dir.join("sub"), dir.join("link")).ok();
+    symlink(dir.join("missing"), dir.join("broken")).ok();
+    fs::write(dir.join("passwd"), "").unwrap();
+    fs::set_permissions(dir.join("passwd"), fs::Permissions::from_mode(0o4755)).unwrap();
+    fs::create_dir(dir.join("tmp")).unwrap();
+    fs::set_permissions(dir.join("tmp"), fs::Permissions::from_mode(0o1777)).unwrap();
+    fs::create_dir(dir.join("shared")).unwrap();
+    fs::set_permissions(dir.join("shared"), fs::Permissions::from_mode(0o777)).unwrap();
+
+    let colors = LsColors::parse("di=01;34:ex=01;32:or=31:su=37;41:tw=30;42:*.tar=01;31:*.sh=35");
+    let theme = Theme::default();
+
+    let file = |name: &str| {
+        let entry = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .find(|entry| entry.file_name() == name)
+            .unwrap();
+        FileData::new(entry).unwrap()
+    };
+    let style = |name: &str| colors.style_for(&file(name), &theme);
+
+    assert_eq!(parse_sgr("01;34"), style("sub"));
+    assert_eq!(parse_sgr("01;32"), style("run.sh"));
+    assert_eq!(parse_sgr("01;31"), style("data.tar"));
+    assert_eq!(parse_sgr("31"), style("broken"));
+    assert_eq!(parse_sgr("37;41"), style("passwd"));
+    assert_eq!(parse_sgr("30;42"), style("tmp"));
+    // no `ow` given, so just a directory
+    assert_eq!(parse_sgr("01;34"), style("shared"));
+    // kinds LS_COLORS leaves out aren't colored from the theme
+    assert_eq!(Style::default(), style("link"));
+
+    // without `ex` an executable is a file like any other
+    let colors = LsColors::parse("*.sh=35");
+    assert_eq!(parse_sgr("35"), colors.style_for(&file("run.sh"), &theme));
+    assert_eq!(Style::default(), colors.style_for(&file("passwd"), &theme));
+
+    // with nothing loaded the theme colors everything
+    let colors = LsColors::default();
+    assert_eq!(theme.kind_style("ex"), colors.style_for(&file("run.sh"), &theme));
+    assert_eq!(theme.kind_style("ln"), colors.style_for(&file("link"), &theme));
+    assert_eq!(theme.kind_style("di"), colors.style_for(&file("tmp"), &theme));
+}
+
+#[test]
+fn test_named_themes() {
+    assert_eq!(Some(Theme::default()), Theme::named("default"));
+    assert!(Theme::named("nord").is_some());
+    assert!(Theme::named("mono").unwrap().highlight_style().add_modifier.contains(Modifier::REVERSED));
+    assert!(Theme::named("neon").is_none());
+}
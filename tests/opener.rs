@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use image::{Rgba, RgbaImage};
+
+use rufile::entry::file_data::FileData;
+use rufile::entry::listing::{read_dir, ListingOptions};
+use rufile::opener::{self, expand, glob_match, is_text, shell_quote, Launch, Opener, Rule};
+
+mod common;
+use common::TempDir;
+
+const RULES: &str = r#"
+[[rule]]
+mime = "text/*"
+command = "vim %f"
+terminal = true
+
+[[rule]]
+glob = "*.pdf"
+command = "zathura %f"
+
+[[rule]]
+command = "xdg-open %f"
+"#;
+
+#[test]
+fn test_parse_rules() {
+    let opener = Opener::parse(RULES).unwrap();
+    assert_eq!(3, opener.rules.len());
+    assert!(opener.rules[0].terminal);
+    assert!(!opener.rules[1].terminal);
+
+    assert!(Opener::parse("").unwrap().rules.is_empty());
+    assert!(Opener::parse("[[rule]]\nglob = \"*\"").is_err());
+    assert!(Opener::parse("[[rule]]\ncommand = \"a\"\nterminl = true").is_err());
+}
+
+#[test]
+fn test_matching_rules() {
+    let opener = Opener::parse(RULES).unwrap();
+    let commands = |name, mime| -> Vec<String> {
+        opener.matching(name, mime).iter().map(|rule| rule.command.clone()).collect()
+    };
+
+    assert_eq!(vec!["vim %f", "xdg-open %f"], commands("notes.md", "text/plain"));
+    assert_eq!(vec!["vim %f", "xdg-open %f"], commands("notes.md", "TEXT/Markdown"));
+    assert_eq!(vec!["zathura %f", "xdg-open %f"], commands("a.pdf", "application/pdf"));
+    assert_eq!(vec!["xdg-open %f"], commands("a.PDF", "application/pdf"));
+}
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("*", ""));
+    assert!(glob_match("*.tar.gz", "a.tar.gz"));
+    assert!(glob_match("image/*", "image/png"));
+    assert!(glob_match("?.rs", "a.rs"));
+    assert!(glob_match("*a*b", "xaxxab"));
+    assert!(!glob_match("*.rs", "a.rsx"));
+    assert!(!glob_match("?.rs", "ab.rs"));
+}
+
+#[test]
+fn test_expand_placeholders() {
+    let marked = vec![PathBuf::from("/tmp/a b"), PathBuf::from("/tmp/c")];
+
+    assert_eq!("cp '/tmp/a b' /tmp/c /home/me/x%",
+               expand("cp %s %d%%", Path::new("/tmp/f"), Path::new("/home/me/x"), &marked));
+    assert_eq!("less /tmp/f %q", expand("less %f %q", Path::new("/tmp/f"), Path::new("/"), &[]));
+
+    assert_eq!("'it'\\''s'", shell_quote("it's"));
+    assert_eq!("''", shell_quote(""));
+    assert_eq!("a/b-c.txt", shell_quote("a/b-c.txt"));
+
+    let opener = Opener::parse(RULES).unwrap();
+    let launch = opener.rules[0].launch(Path::new("/tmp/$x"), Path::new("/tmp"), &[]);
+    assert_eq!(Launch { command: String::from("vim '/tmp/$x'"), terminal: true }, launch);
+}
+
+#[test]
+fn test_text_files_go_to_the_editor() {
+    let opener = Opener::default();
+    let text = opener.matching("notes", "text/plain");
+    assert_eq!(2, text.len());
+    assert!(text[0].terminal);
+    assert!(text[0].command.contains("EDITOR"));
+
+    let binary = opener.matching("a.png", "image/png");
+    assert_eq!(vec!["xdg-open %f"], binary.iter().map(|rule| &rule.command).collect::<Vec<_>>());
+
+    let launch = Rule::pager().launch(Path::new("a b"), Path::new("/"), &[]);
+    assert_eq!("${PAGER:-less} 'a b'", launch.command);
+    assert!(launch.terminal);
+}
+
+#[test]
+fn test_sniff_text() {
+    let dir = TempDir::new("sniff");
+
+    fs::write(dir.join("empty"), "").unwrap();
+    fs::write(dir.join("utf8"), "grüße\n").unwrap();
+    fs::write(dir.join("nul"), b"ab\0cd").unwrap();
+    fs::write(dir.join("latin1"), b"gr\xfc\xdfe\n").unwrap();
+    // a character cut in half by the sniffed size is still text
+    let mut cut = "a".repeat(1023).into_bytes();
+    cut.extend_from_slice("ü".as_bytes());
+    fs::write(dir.join("cut"), cut).unwrap();
+
+    assert!(is_text(&dir.join("empty")));
+    assert!(is_text(&dir.join("utf8")));
+    assert!(is_text(&dir.join("cut")));
+    assert!(!is_text(&dir.join("nul")));
+    assert!(!is_text(&dir.join("latin1")));
+    assert!(!is_text(&dir.join("missing")));
+}
+
+#[test]
+fn test_mime_rules_match_real_files() {
+    let dir = TempDir::new("mime");
+    RgbaImage::from_pixel(2, 2, Rgba([0, 0, 0, 255])).save(dir.join("photo.png")).unwrap();
+    fs::copy(dir.join("photo.png"), dir.join("photo")).unwrap();
+
+    let opener = Opener::parse("[[rule]]\nmime = \"image/*\"\ncommand = \"feh %f\"").unwrap();
+    let files: Vec<FileData> = read_dir(&dir, &ListingOptions::default()).unwrap();
+    assert_eq!(2, files.len());
+    for file in &files {
+        let mime = opener::mime_type(file);
+        assert_eq!("image/png", mime);
+        assert_eq!(1, opener.matching(&file.name, &mime).len());
+    }
+}
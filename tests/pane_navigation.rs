@@ -0,0 +1,91 @@
+use std::fs;
+
+use rufile::entry::listing::ListingOptions;
+use rufile::pane::Pane;
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_cursor_memory() {
+    let dir = TempDir::new("pane");
+    fs::create_dir_all(dir.join("a")).unwrap();
+    fs::create_dir_all(dir.join("b/x")).unwrap();
+    fs::write(dir.join("b/y"), "").unwrap();
+    fs::write(dir.join("b/z"), "").unwrap();
+
+    let mut pane = Pane::new(dir.to_path_buf(), ListingOptions::default());
+    pane.select_index(1);
+    assert_eq!(Some(String::from("b")), pane.selected_name());
+
+    pane.enter_dir("b").unwrap();
+    assert_eq!(dir.join("b"), pane.path);
+    assert_eq!(Some(String::from("x")), pane.selected_name());
+    pane.move_selection(2);
+    assert_eq!(Some(String::from("z")), pane.selected_name());
+
+    pane.go_parent();
+    assert_eq!(*dir, pane.path);
+    assert_eq!(Some(String::from("b")), pane.selected_name());
+
+    pane.select_index(0);
+    pane.enter_dir("b").unwrap();
+    assert_eq!(Some(String::from("z")), pane.selected_name());
+    assert_eq!(Some("a"), pane.cursor_memory.recall(&dir));
+}
+
+#[test]
+fn test_back_and_forward() {
+    let dir = TempDir::new("history");
+    fs::create_dir_all(dir.join("a/b")).unwrap();
+    fs::create_dir_all(dir.join("c")).unwrap();
+
+    let mut pane = Pane::new(dir.to_path_buf(), ListingOptions::default());
+    pane.enter_dir("a").unwrap();
+    pane.enter_dir("b").unwrap();
+    pane.change_dir(dir.join("c")).unwrap();
+
+    pane.go_back();
+    assert_eq!(dir.join("a/b"), pane.path);
+    pane.go_back();
+    assert_eq!(dir.join("a"), pane.path);
+    pane.go_forward();
+    assert_eq!(dir.join("a/b"), pane.path);
+
+    // a new jump drops the forward history
+    pane.go_parent();
+    pane.go_forward();
+    assert_eq!(dir.join("a"), pane.path);
+
+    // directories that are gone are skipped
+    fs::remove_dir_all(dir.join("a/b")).unwrap();
+    pane.go_back();
+    assert_eq!(*dir, pane.path);
+    pane.go_back();
+    assert_eq!(*dir, pane.path);
+}
+
+#[test]
+fn test_marked_selection() {
+    let dir = TempDir::new("marks");
+    for name in ["a", "b", "c"] {
+        fs::write(dir.join(name), "").unwrap();
+    }
+
+    let mut pane = Pane::new(dir.to_path_buf(), ListingOptions::default());
+    assert_eq!(vec![dir.join("a")], pane.chosen_paths());
+
+    pane.toggle_select();
+    pane.move_selection(1);
+    pane.toggle_select();
+    assert_eq!(Some(String::from("c")), pane.selected_name());
+    assert_eq!(vec![dir.join("a"), dir.join("c")], pane.chosen_paths());
+
+    // marking the last entry keeps the cursor there
+    pane.toggle_select();
+    assert_eq!(Some(String::from("c")), pane.selected_name());
+    assert_eq!(vec![dir.join("a")], pane.chosen_paths());
+
+    pane.selection.clear();
+    assert_eq!(vec![dir.join("c")], pane.chosen_paths());
+}
@@ -0,0 +1,286 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use bzip2::write::BzEncoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use image::{Rgba, RgbaImage};
+use lzma_rust2::{XzOptions, XzWriter};
+use tui::style::Color;
+
+use rufile::compressed::{self, Codec};
+use rufile::entry::file_data::FileData;
+use rufile::entry::listing::{read_dir, ListingOptions};
+use rufile::pane::PreviewState;
+use rufile::preview::dir::{self as dir_preview, Summary};
+use rufile::preview::{self, hex, highlight, image as image_preview};
+
+mod common;
+use common::TempDir;
+
+
+fn file_data(dir: &Path, name: &str) -> FileData {
+    read_dir(dir, &ListingOptions::default())
+        .unwrap()
+        .into_iter()
+        .find(|file| file.name == name)
+        .unwrap()
+}
+
+#[test]
+fn test_highlighted_preview() {
+    let dir = TempDir::new("highlight");
+    let source: String = (0..50).map(|n| format!("fn f{}() {{}}\n", n)).collect();
+    fs::write(dir.join("main.rs"), source).unwrap();
+    fs::write(dir.join("run"), "#!/usr/bin/env python3\nprint('hi')\n").unwrap();
+    fs::write(dir.join("Makefile"), "all:\n\tcc main.c\n").unwrap();
+    fs::write(dir.join("notes"), "just\ttext\n").unwrap();
+
+    let rust = file_data(&dir, "main.rs");
+    assert_eq!("Rust", highlight::find_syntax(&rust, "").name);
+    let lines = preview::file_preview(&rust, &mut PreviewState::default(), 80, 20);
+    assert_eq!(20, lines.len());
+    assert!(lines[0].0.len() > 1);
+    assert!(lines[0].0.iter().all(|span| matches!(span.style.fg, Some(Color::Rgb(..)))));
+    assert_eq!("fn f0() {}", lines[0].0.iter().map(|span| span.content.as_ref()).collect::<String>());
+
+    assert_eq!("Python", highlight::find_syntax(&file_data(&dir, "run"), "#!/usr/bin/env python3").name);
+    assert_eq!("Makefile", highlight::find_syntax(&file_data(&dir, "Makefile"), "all:").name);
+
+    let notes = preview::file_preview(&file_data(&dir, "notes"), &mut PreviewState::default(), 80, 20);
+    assert_eq!(1, notes.len());
+    assert_eq!("just    text", notes[0].0[0].content);
+    assert_eq!(None, notes[0].0[0].style.fg);
+}
+
+#[test]
+fn test_preview_cache() {
+    let dir = TempDir::new("preview-cache");
+    let path = dir.join("notes.txt");
+    let lines: String = (0..50).map(|n| format!("line {}\n", n)).collect();
+    fs::write(&path, lines).unwrap();
+    let modified = fs::metadata(&path).unwrap().modified().unwrap();
+
+    let file = file_data(&dir, "notes.txt");
+    let mut state = PreviewState::default();
+    preview::file_preview(&file, &mut state, 80, 10);
+    assert!(matches!(state.kind, Some(preview::Kind::Text)));
+    assert_eq!(Some("text/plain"), state.mime.as_deref());
+    assert_eq!(Some(10), state.text.as_ref().map(|(reach, _)| *reach));
+
+    // highlighted further only once scrolled past what was
+    state.scroll = 5;
+    let shown = preview::file_preview(&file, &mut state, 80, 10);
+    assert_eq!("line 5", text(&shown[0]));
+    assert_eq!(Some(15), state.text.as_ref().map(|(reach, _)| *reach));
+    state.scroll = 0;
+    preview::file_preview(&file, &mut state, 80, 10);
+    assert_eq!(Some(15), state.text.as_ref().map(|(reach, _)| *reach));
+
+    // kept while the file looks unchanged, read again once it has changed
+    fs::write(&path, "changed\n").unwrap();
+    File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+    assert_eq!("line 0", text(&preview::file_preview(&file, &mut state, 80, 10)[0]));
+
+    let later = modified + Duration::from_secs(10);
+    File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+    let shown = preview::file_preview(&file, &mut state, 80, 10);
+    assert_eq!(vec!["changed"], shown.iter().map(text).collect::<Vec<_>>());
+}
+
+fn text(spans: &tui::text::Spans) -> String {
+    spans.0.iter().map(|span| span.content.as_ref()).collect()
+}
+
+#[test]
+fn test_hex_preview() {
+    let dir = TempDir::new("hex");
+    let mut elf = b"\x7fELF\x02\x01\x01\0".to_vec();
+    elf.extend((0..100u8).collect::<Vec<u8>>());
+    fs::write(dir.join("a.out"), &elf).unwrap();
+    fs::write(dir.join("latin1.txt"), b"gr\xfc\xdfe\n").unwrap();
+    fs::write(dir.join("plain.txt"), "hello world\n").unwrap();
+    // valid UTF-8 without NULs, but libmagic calls it data
+    fs::write(dir.join("control"), (1..=8u8).chain(14..=31).collect::<Vec<u8>>()).unwrap();
+
+    assert_eq!("00000000: 7f45 4c46 0201 0100 0001 0203 0405 0607  .ELF............",
+               text(&hex::format_line(0, &elf[..16])));
+    assert_eq!("00000010: 4142 43                                  ABC",
+               text(&hex::format_line(16, b"ABC")));
+    assert_eq!(7, hex::line_count(elf.len() as u64));
+
+    let binary = file_data(&dir, "a.out");
+    assert!(preview::is_binary(&binary));
+    assert!(preview::is_binary(&file_data(&dir, "latin1.txt")));
+    assert!(!preview::is_binary(&file_data(&dir, "plain.txt")));
+    assert!(preview::is_binary(&file_data(&dir, "control")));
+
+    // scrolling stops once the last line is in view
+    let mut state = PreviewState::default();
+    state.follow(Some(binary.path()));
+    state.scroll = 100;
+    let lines = preview::file_preview(&binary, &mut state, 80, 3);
+    assert_eq!(4, state.scroll);
+    assert_eq!(3, lines.len());
+    assert!(text(&lines[0]).starts_with("00000040: "));
+
+    // another file starts at the top again
+    let plain = file_data(&dir, "plain.txt");
+    let lines = preview::file_preview(&plain, &mut state, 80, 3);
+    assert_eq!(0, state.scroll);
+    assert_eq!("hello world", text(&lines[0]));
+
+    state.hex = true;
+    let lines = preview::file_preview(&plain, &mut state, 80, 3);
+    assert_eq!("00000000: 6865 6c6c 6f20 776f 726c 640a            hello world.", text(&lines[0]));
+}
+
+#[test]
+fn test_directory_summary() {
+    let dir = TempDir::new("summary");
+    fs::create_dir_all(dir.join("src/nested")).unwrap();
+    fs::write(dir.join("src/nested/deep.txt"), vec![b'x'; 1000]).unwrap();
+    fs::write(dir.join("src/lib.rs"), vec![b'x'; 500]).unwrap();
+    fs::write(dir.join("README"), vec![b'x'; 34]).unwrap();
+    fs::create_dir(dir.join("empty")).unwrap();
+
+    let files = read_dir(&dir, &ListingOptions::default()).unwrap();
+    let summary = Summary::new(&dir, &files);
+    assert_eq!((2, 1), (summary.dirs, summary.files));
+
+    // the total is added up in the background
+    let mut size = summary.size;
+    for _ in 0..100 {
+        if size.is_some() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+        size = dir_preview::total_size(&dir);
+    }
+    let size = size.unwrap();
+    assert_eq!(1534, size);
+    let summary = Summary::new(&dir, &files);
+    assert_eq!(Some(size), summary.size);
+    assert!(summary.to_string().starts_with("2 dirs, 1 files, "));
+
+    // moving on cancels the directory being added up and starts the next
+    dir_preview::total_size(&dir.join("empty"));
+    dir_preview::cancel();
+    let mut size = None;
+    for _ in 0..100 {
+        size = dir_preview::total_size(&dir.join("src"));
+        if size.is_some() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(Some(1500), size);
+    assert_eq!(Some(1534), dir_preview::total_size(&dir));
+}
+
+#[test]
+fn test_compressed_preview() {
+    let dir = TempDir::new("compressed");
+    let source: String = (0..50).map(|n| format!("fn f{}() {{}}\n", n)).collect();
+
+    let mut gzip = GzEncoder::new(File::create(dir.join("main.rs.gz")).unwrap(), Compression::fast());
+    gzip.write_all(source.as_bytes()).unwrap();
+    gzip.finish().unwrap();
+    let mut xz = XzWriter::new(File::create(dir.join("main.rs.xz")).unwrap(), XzOptions::with_preset(1)).unwrap();
+    xz.write_all(source.as_bytes()).unwrap();
+    xz.finish().unwrap();
+    fs::write(dir.join("main.rs.zst"), zstd::encode_all(source.as_bytes(), 0).unwrap()).unwrap();
+    let mut bzip2 = BzEncoder::new(File::create(dir.join("main.rs.BZ2")).unwrap(), bzip2::Compression::fast());
+    bzip2.write_all(source.as_bytes()).unwrap();
+    bzip2.finish().unwrap();
+    fs::write(dir.join("blob.gz"), {
+        let mut blob = GzEncoder::new(Vec::new(), Compression::fast());
+        blob.write_all(b"\x7fELF\x02\x01\x01\0").unwrap();
+        blob.finish().unwrap()
+    }).unwrap();
+
+    assert_eq!(Some(("main.rs", Codec::Bzip2)), Codec::split_extension("main.rs.BZ2"));
+    assert_eq!(None, Codec::split_extension("main.rs"));
+    assert_eq!(10, compressed::head(&dir.join("main.rs.gz"), Codec::Gzip, 10).unwrap().len());
+
+    for (name, codec) in [("main.rs.gz", "gzip"), ("main.rs.xz", "xz"),
+                          ("main.rs.zst", "zstd"), ("main.rs.BZ2", "bzip2")] {
+        let mut state = PreviewState::default();
+        let lines = preview::file_preview(&file_data(&dir, name), &mut state, 80, 20);
+        assert_eq!(Some(codec), state.header.as_deref());
+        assert_eq!(20, lines.len());
+        assert_eq!("fn f0() {}", text(&lines[0]));
+        assert!(lines[0].0.iter().all(|span| matches!(span.style.fg, Some(Color::Rgb(..)))));
+
+        state.scroll = 100;
+        let lines = preview::file_preview(&file_data(&dir, name), &mut state, 80, 20);
+        assert_eq!(30, state.scroll);
+        assert_eq!("fn f30() {}", text(&lines[0]));
+    }
+
+    // what isn't text once decompressed is dumped as hex
+    let blob = file_data(&dir, "blob.gz");
+    let mut state = PreviewState::default();
+    let lines = preview::file_preview(&blob, &mut state, 80, 20);
+    assert_eq!(1, lines.len());
+    assert_eq!("00000000: 7f45 4c46 0201 0100                      .ELF....", text(&lines[0]));
+
+    // forcing hex shows the compressed bytes
+    state.hex = true;
+    let lines = preview::file_preview(&blob, &mut state, 80, 20);
+    assert!(text(&lines[0]).starts_with("00000000: 1f8b"));
+    assert_eq!(None, state.header);
+}
+
+#[test]
+fn test_image_preview() {
+    let dir = TempDir::new("image");
+    // red above blue, with the top right pixel see-through
+    let mut small = RgbaImage::from_pixel(4, 2, Rgba([255, 0, 0, 255]));
+    for x in 0..4 {
+        small.put_pixel(x, 1, Rgba([0, 0, 255, 255]));
+    }
+    small.put_pixel(3, 0, Rgba([0, 0, 0, 0]));
+    small.save(dir.join("small.png")).unwrap();
+    RgbaImage::from_pixel(100, 50, Rgba([9, 9, 9, 255])).save(dir.join("wide.png")).unwrap();
+    // detected by MIME type, not its name
+    fs::copy(dir.join("small.png"), dir.join("small.dat")).unwrap();
+
+    let png = file_data(&dir, "small.dat");
+    assert!(image_preview::detect(&png).is_some());
+    assert!(image_preview::detect(&file_data(&dir, "wide.png")).is_some());
+
+    let mut state = PreviewState::default();
+    let lines = preview::file_preview(&png, &mut state, 4, 1);
+    assert_eq!(1, lines.len());
+    assert_eq!(4, lines[0].0.len());
+    assert_eq!("▀", lines[0].0[0].content);
+    assert_eq!("▄", lines[0].0[3].content);
+    assert!(state.image.is_some());
+    assert_eq!(Some("4x2, RGBA 8-bit"), state.image_info.as_deref());
+
+    let decoded = image_preview::decode(png.path(), ::image::ImageFormat::Png).unwrap();
+    let lines = image_preview::render(&decoded, 4, 1, true);
+    assert_eq!((Some(Color::Rgb(255, 0, 0)), Some(Color::Rgb(0, 0, 255))),
+               (lines[0].0[0].style.fg, lines[0].0[0].style.bg));
+    assert_eq!((Some(Color::Rgb(0, 0, 255)), None), (lines[0].0[3].style.fg, lines[0].0[3].style.bg));
+
+    // scaled to fit the pane, two pixels to a row
+    let wide = image_preview::decode(&dir.join("wide.png"), ::image::ImageFormat::Png).unwrap();
+    let lines = image_preview::render(&wide, 10, 10, false);
+    assert_eq!(3, lines.len());
+    assert_eq!(10, lines[0].0.len());
+    assert_eq!(Some(Color::Indexed(232)), lines[0].0[0].style.fg);
+
+    assert_eq!(196, image_preview::ansi256(255, 0, 0));
+    assert_eq!(21, image_preview::ansi256(0, 0, 255));
+    assert_eq!(16, image_preview::ansi256(0, 0, 0));
+    assert_eq!(244, image_preview::ansi256(128, 128, 128));
+
+    state.hex = true;
+    let lines = preview::file_preview(&png, &mut state, 80, 1);
+    assert!(text(&lines[0]).starts_with("00000000: 8950 4e47"));
+}
@@ -0,0 +1,74 @@
+use std::env;
+use std::fs;
+
+use rufile::command_input::input::{CommandHandler, InputMode};
+use rufile::entry::listing::{ListingOptions, SortKey};
+use rufile::keymap::Action;
+use rufile::pane::DualPane;
+use rufile::tabs::Tabs;
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_tabs_session() {
+    let dir = TempDir::new("tabs");
+    fs::create_dir_all(dir.join("one")).unwrap();
+    fs::create_dir_all(dir.join("two")).unwrap();
+    fs::write(dir.join("two/notes.txt"), "").unwrap();
+    fs::write(dir.join("two/todo.txt"), "").unwrap();
+
+    let opts = ListingOptions::default();
+    let mut tabs = Tabs::new(DualPane::new(dir.join("one"), opts.clone()));
+    tabs.open(dir.join("two"), opts.clone());
+    tabs.duplicate();
+    assert_eq!(vec!["one", "two", "two"], tabs.titles());
+    assert_eq!(2, tabs.active_index());
+
+    let pane = tabs.active_mut().active_mut();
+    pane.listing.sort = SortKey::Size;
+    pane.listing.filter = Some(String::from("TODO"));
+    pane.sync_selection(&pane.files());
+    assert_eq!(Some(String::from("todo.txt")), pane.selected_name());
+
+    tabs.select_next();
+    assert_eq!(0, tabs.active_index());
+    assert_eq!(dir.join("one"), env::current_dir().unwrap());
+    tabs.select(1);
+    assert_eq!(SortKey::Name, tabs.active().active().listing.sort);
+    tabs.select_prev();
+    tabs.select_prev();
+    assert_eq!(2, tabs.active_index());
+
+    let session = dir.join("tabs.toml");
+    tabs.save(&session).unwrap();
+    fs::remove_dir_all(dir.join("one")).unwrap();
+
+    let restored = Tabs::load(&session, &opts).unwrap();
+    assert_eq!(vec!["two", "two"], restored.titles());
+    assert_eq!(1, restored.active_index());
+    let pane = restored.active().active();
+    assert_eq!(SortKey::Size, pane.listing.sort);
+    assert_eq!(Some(String::from("TODO")), pane.listing.filter);
+    assert_eq!(Some(String::from("todo.txt")), pane.selected_name());
+
+    let mut tabs = restored;
+    assert!(tabs.close());
+    assert!(!tabs.close());
+}
+
+#[test]
+fn test_action_argument() {
+    let mut command = CommandHandler::default();
+    command.run(":filter  foo bar ", None);
+    assert_eq!(InputMode::Normal, command.input_mode);
+    assert_eq!(Some((Action::Filter, None)), command.take_action());
+    assert_eq!(Some(String::from("foo bar")), command.take_argument());
+
+    command.run(":filter", None);
+    assert_eq!(Some(String::new()), command.take_argument());
+
+    command.run(":down 3", None);
+    assert_eq!(Some((Action::Down, Some(3))), command.take_action());
+    assert_eq!(None, command.take_argument());
+}
@@ -0,0 +1,62 @@
+use std::fs;
+
+use rufile::entry::listing::ListingOptions;
+use rufile::pane::Pane;
+
+mod common;
+use common::TempDir;
+
+fn names(pane: &Pane) -> Vec<String> {
+    pane.nodes()
+        .iter()
+        .map(|node| format!("{}{}", node.guides, node.file.name))
+        .collect()
+}
+
+#[test]
+fn test_expand_and_collapse() {
+    let dir = TempDir::new("tree");
+    fs::create_dir_all(dir.join("src/view")).unwrap();
+    fs::write(dir.join("src/main.rs"), "").unwrap();
+    fs::write(dir.join("src/view/mod.rs"), "").unwrap();
+    fs::write(dir.join("Cargo.toml"), "").unwrap();
+
+    let mut pane = Pane::new(dir.to_path_buf(), ListingOptions::default());
+    pane.select_index(0);
+    pane.toggle_tree();
+    assert_eq!(vec!["src", "Cargo.toml"], names(&pane));
+
+    pane.expand();
+    pane.move_selection(1);
+    pane.expand();
+    assert_eq!(vec!["src", "├─ view", "│  └─ mod.rs", "└─ main.rs", "Cargo.toml"], names(&pane));
+    assert_eq!(Some(dir.join("src/view")), pane.selected_path());
+
+    // the flat listing keeps the top level entry the cursor was in
+    pane.move_selection(1);
+    assert_eq!(Some(dir.join("src/view/mod.rs")), pane.selected_path());
+    pane.toggle_tree();
+    assert_eq!(Some(dir.join("src")), pane.selected_path());
+    pane.toggle_tree();
+    assert_eq!(vec!["src", "Cargo.toml"], names(&pane));
+    pane.expand();
+    pane.move_selection(1);
+    pane.expand();
+    pane.select_path(&dir.join("src/view/mod.rs"));
+
+    pane.collapse();
+    assert_eq!(Some(dir.join("src/view")), pane.selected_path());
+    assert_eq!(vec!["src", "├─ view", "└─ main.rs", "Cargo.toml"], names(&pane));
+    // one walk gives both the files and the nodes they are drawn as
+    let (files, nodes) = pane.listing();
+    assert_eq!(vec!["src", "view", "main.rs", "Cargo.toml"],
+               files.iter().map(|file| file.name.as_str()).collect::<Vec<_>>());
+    assert_eq!(files.len(), nodes.len());
+
+    pane.collapse();
+    assert_eq!(Some(dir.join("src")), pane.selected_path());
+    assert_eq!(vec!["src", "Cargo.toml"], names(&pane));
+
+    pane.collapse();
+    assert_eq!(dir.parent().unwrap(), pane.path);
+}